@@ -0,0 +1,89 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::sync::Arc;
+use unit_conv::{
+    CacheConfig, Command, ConversionCache, Convertable, CurrencyUnit, ExecutionContext, LengthUnit,
+    MassUnit, MockExchangeRateProvider, Unit, Value,
+};
+
+/// One million meter to kilometer conversions.
+fn convert_lengths(c: &mut Criterion) {
+    c.bench_function("1M LengthUnit conversions", |b| {
+        b.iter(|| {
+            for i in 0..1_000_000 {
+                black_box(
+                    LengthUnit::convert(
+                        black_box(f64::from(i)),
+                        &LengthUnit::Meter,
+                        &LengthUnit::Kilometer,
+                    )
+                    .unwrap(),
+                );
+            }
+        })
+    });
+}
+
+/// One million kilogram to ounce conversions.
+fn convert_masses(c: &mut Criterion) {
+    c.bench_function("1M MassUnit conversions", |b| {
+        b.iter(|| {
+            for i in 0..1_000_000 {
+                black_box(
+                    MassUnit::convert(
+                        black_box(f64::from(i)),
+                        &MassUnit::Kilogram,
+                        &MassUnit::Ounce,
+                    )
+                    .unwrap(),
+                );
+            }
+        })
+    });
+}
+
+/// Currency conversions with the fixed rates of the mock provider, without network access.
+fn convert_currencies(c: &mut Criterion) {
+    let ctx = ExecutionContext::new(ConversionCache::with_config(CacheConfig {
+        db_path: ":memory:".into(),
+        provider: Arc::new(MockExchangeRateProvider::default()),
+        ..Default::default()
+    }));
+    let usd = Value::new(100.0, Unit::Currency(CurrencyUnit::USD));
+    let eur = Unit::Currency(CurrencyUnit::EUR);
+
+    c.bench_function("1K CurrencyUnit conversions", |b| {
+        b.iter(|| {
+            for _ in 0..1_000 {
+                black_box(black_box(usd).convert_to_with(&eur, &ctx).unwrap());
+            }
+        })
+    });
+}
+
+fn parse_commands(c: &mut Criterion) {
+    c.bench_function("10K Command::from_str calls", |b| {
+        b.iter(|| {
+            for _ in 0..10_000 {
+                black_box(black_box("100 km -> mi").parse::<Command>().unwrap());
+            }
+        })
+    });
+}
+
+fn list_units(c: &mut Criterion) {
+    c.bench_function("1K Unit::get_all_units calls", |b| {
+        b.iter(|| {
+            for _ in 0..1_000 {
+                black_box(Unit::get_all_units());
+            }
+        })
+    });
+}
+
+criterion_group! {
+    name = benches;
+    // few samples keep the 1M conversion runs short, also with `cargo bench -- --test`
+    config = Criterion::default().sample_size(10);
+    targets = convert_lengths, convert_masses, convert_currencies, parse_commands, list_units
+}
+criterion_main!(benches);
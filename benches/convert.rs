@@ -0,0 +1,59 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use strum::IntoEnumIterator;
+use unit_conv::{convert, convert_uncached, Convertable, LengthUnit, Unit};
+
+/// Convert 1000 values between every pair of length units.
+fn convert_lengths(c: &mut Criterion) {
+    let values: Vec<f64> = (0..1000).map(f64::from).collect();
+    let pairs: Vec<(LengthUnit, LengthUnit)> = LengthUnit::iter()
+        .flat_map(|from| LengthUnit::iter().map(move |to| (from, to)))
+        .collect();
+
+    c.bench_function("convert LengthUnit batch", |b| {
+        b.iter(|| {
+            for (from, to) in &pairs {
+                for value in &values {
+                    black_box(LengthUnit::convert(black_box(*value), from, to).unwrap());
+                }
+            }
+        })
+    });
+
+    // the previous implementation, going through the base unit for every value
+    c.bench_function("convert LengthUnit batch via base unit", |b| {
+        b.iter(|| {
+            for (from, to) in &pairs {
+                for value in &values {
+                    let base = from.to_base_unit(black_box(*value)).unwrap();
+                    black_box(base / to.to_base_unit(1.0).unwrap());
+                }
+            }
+        })
+    });
+}
+
+/// One million meter to kilometer conversions through `Unit`, with the cached factor
+/// and with the constant factors of both units.
+fn convert_repeated(c: &mut Criterion) {
+    let m = Unit::Length(LengthUnit::Meter);
+    let km = Unit::Length(LengthUnit::Kilometer);
+
+    c.bench_function("1M meter to kilometer conversions", |b| {
+        b.iter(|| {
+            for i in 0..1_000_000 {
+                black_box(convert(black_box(f64::from(i)), m, km).unwrap());
+            }
+        })
+    });
+
+    c.bench_function("1M meter to kilometer conversions without cache", |b| {
+        b.iter(|| {
+            for i in 0..1_000_000 {
+                black_box(convert_uncached(black_box(f64::from(i)), m, km).unwrap());
+            }
+        })
+    });
+}
+
+criterion_group!(benches, convert_lengths, convert_repeated);
+criterion_main!(benches);
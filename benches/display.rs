@@ -0,0 +1,51 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use unit_conv::{LengthUnit, Unit};
+
+fn format_unit(c: &mut Criterion) {
+    c.bench_function("format LengthUnit::Meter", |b| {
+        b.iter(|| black_box(LengthUnit::Meter).to_string())
+    });
+}
+
+fn parse_unit(c: &mut Criterion) {
+    c.bench_function("parse \"km\"", |b| {
+        b.iter(|| black_box("km").parse::<Unit>())
+    });
+}
+
+fn format_all_units(c: &mut Criterion) {
+    c.bench_function("format all units", |b| {
+        b.iter(|| {
+            for unit in Unit::all_units() {
+                black_box(unit.to_string());
+            }
+        })
+    });
+}
+
+/// The shared list of `all_units` avoids allocating a new one on every call.
+fn list_all_units(c: &mut Criterion) {
+    c.bench_function("10k all_units calls", |b| {
+        b.iter(|| {
+            for _ in 0..10_000 {
+                black_box(Unit::all_units());
+            }
+        })
+    });
+    c.bench_function("10k get_all_units calls", |b| {
+        b.iter(|| {
+            for _ in 0..10_000 {
+                black_box(Unit::get_all_units());
+            }
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    format_unit,
+    parse_unit,
+    format_all_units,
+    list_all_units
+);
+criterion_main!(benches);
@@ -1,72 +1,622 @@
 use regex::Regex;
+use serde_json::json;
+use std::io::{self, BufRead, Write};
 use std::{num::ParseFloatError, str::FromStr};
 
-use crate::core::units::{Unit, Value};
+use crate::core::context::ExecutionContext;
+use crate::core::currency::APIError;
+use crate::core::providers::store_api_key;
+use crate::core::units::{
+    interpolate_with, ConversionChain, ConversionError, ConversionResult, FormatOptions,
+    NumeralSystem, Unit, UnitCategory, Value,
+};
+use strum::IntoEnumIterator;
+
+/// Shown when the exchange rate API rejects the API key.
+const AUTH_ERROR_MESSAGE: &str =
+    "Authentication failed — check your OPENEXCHANGERATES_APP_ID environment variable.";
 
 /// Command enum to represent the different commands the user can input.
 #[derive(Debug, PartialEq)]
 pub enum Command {
     /// Convert a value to another unit.
     Convert(Value, Unit),
+    /// Convert a value to all units of its category.
+    ConvertAll(Value),
+    /// Convert the sum of several values, e.g. `5 ft 11 in -> cm`, to a unit,
+    /// or to all units of its category without one.
+    ConvertCompound(Vec<Value>, Option<Unit>),
+    /// Find the value in a unit that converts to the given value, e.g. `? m -> 1 km`.
+    Invert(Value, Unit),
+    /// Convert a value through several units, e.g. `1 kg -> lb -> oz`.
+    Chain(Value, Vec<Unit>),
+    /// Add two values and convert the sum to a unit.
+    Add(Value, Value, Unit),
+    /// Value at a fraction of the way between two values, e.g. `50% between 0 °C and 100 °C`.
+    Interpolate(f64, Value, Value),
     /// List all available units.
     Units,
+    /// List the units of one category.
+    CategoryUnits(UnitCategory),
+    /// List the units a unit can be converted to.
+    CompatibleUnits(Unit),
+    /// Drop the cached currency rates so they are requested again.
+    InvalidateCache,
+    /// Show the previously entered conversions.
+    History,
+    /// Forget the previously entered conversions.
+    HistoryClear,
+    /// Run the n-th history entry again (1-based).
+    Recall(usize),
+    /// Show the effective configuration.
+    Config,
+    /// Store the API key of openexchangerates.org in the system keyring.
+    SetKey(String),
+    /// Several commands separated by `;`, with the error of those that could not be parsed.
+    Multi(Vec<Result<Command, String>>),
     /// Show help.
     Help,
+    /// Show the version and build information.
+    About,
     /// Exit the program.
     Exit,
 }
 
+/// Output of an executed command.
+#[derive(Debug, PartialEq)]
+pub struct CommandResult {
+    /// Text to show to the user.
+    pub output: String,
+    /// Whether the output describes a failure.
+    pub is_error: bool,
+    /// Whether the program should exit.
+    pub exit: bool,
+    /// Input and result of a conversion, for structured output.
+    pub conversion: Option<Conversion>,
+}
+
+/// Values of an executed conversion.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Conversion {
+    /// Value that was converted.
+    pub input: Value,
+    /// Unit the value was converted to.
+    pub to: Unit,
+    /// Converted value, `None` if the conversion failed.
+    pub output: Option<Value>,
+}
+
+impl CommandResult {
+    /// Successful output.
+    pub fn ok(output: impl Into<String>) -> Self {
+        CommandResult {
+            output: output.into(),
+            is_error: false,
+            exit: false,
+            conversion: None,
+        }
+    }
+
+    /// Output describing a failure.
+    pub fn error(output: impl Into<String>) -> Self {
+        CommandResult {
+            output: output.into(),
+            is_error: true,
+            exit: false,
+            conversion: None,
+        }
+    }
+
+    /// No output, the program should exit.
+    pub fn exit() -> Self {
+        CommandResult {
+            output: String::new(),
+            is_error: false,
+            exit: true,
+            conversion: None,
+        }
+    }
+
+    fn converted(result: ConversionResult<Value>, options: &FormatOptions) -> Self {
+        match result {
+            Ok(v) => CommandResult::ok(v.format(options)),
+            Err(e) => CommandResult::error(error_message(&e)),
+        }
+    }
+
+    /// Result of converting `input` to `to`, keeping the values for structured output.
+    fn conversion(
+        input: &Value,
+        to: &Unit,
+        result: ConversionResult<Value>,
+        options: &FormatOptions,
+    ) -> Self {
+        let output = result.as_ref().ok().copied();
+        CommandResult {
+            conversion: Some(Conversion {
+                input: *input,
+                to: *to,
+                output,
+            }),
+            ..CommandResult::converted(result, options)
+        }
+    }
+
+    /// One line of CSV with the columns of `CSV_HEADER`.
+    /// Only the error column is filled for commands other than conversions.
+    pub fn to_csv_line(&self) -> String {
+        let error = if self.is_error {
+            self.output.as_str()
+        } else {
+            ""
+        };
+        let (input_value, input_unit, output_value, output_unit) = match &self.conversion {
+            Some(conversion) => (
+                number(conversion.input.value()),
+                conversion.input.unit().abbreviation(),
+                number(conversion.output.and_then(|v| v.value())),
+                conversion.to.abbreviation(),
+            ),
+            None => Default::default(),
+        };
+        [&input_value, input_unit, &output_value, output_unit, error]
+            .map(csv_field)
+            .join(",")
+    }
+}
+
+/// Columns written by `CommandResult::to_csv_line`.
+pub const CSV_HEADER: &str = "input_value,input_unit,output_value,output_unit,error";
+
+fn number(value: Option<f64>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+/// Quote a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Message shown for a failed conversion, with a hint on how to fix a rejected API key.
+fn error_message(e: &ConversionError) -> String {
+    let auth_failed = std::error::Error::source(e)
+        .and_then(|source| source.downcast_ref::<APIError>())
+        .is_some_and(APIError::is_auth_error);
+    if auth_failed {
+        AUTH_ERROR_MESSAGE.to_string()
+    } else {
+        e.to_string()
+    }
+}
+
 impl Command {
-    /// Execute the command and return the output as a string.
+    /// Execute the command and return its output.
     /// String output is chosen to support different UIs.
-    pub fn execute(&self) -> String {
-        let mut output = String::new();
+    /// Currencies are converted with the exchange rates of the given context.
+    pub fn execute(&self, ctx: &ExecutionContext) -> CommandResult {
+        self.execute_with(ctx, &FormatOptions::default())
+    }
 
+    /// Execute the command and render values according to the given options.
+    pub fn execute_with(&self, ctx: &ExecutionContext, options: &FormatOptions) -> CommandResult {
         match self {
-            Command::Convert(value, to_unit) => {
-                let result = value.convert_to(to_unit);
+            Command::Convert(value, to_unit) => CommandResult::conversion(
+                value,
+                to_unit,
+                value.convert_to_with(to_unit, ctx),
+                options,
+            ),
+            Command::ConvertAll(value) => {
+                CommandResult::ok(value.to_conversion_table_with(ctx).format(options))
+            }
+            Command::ConvertCompound(values, to_unit) => {
+                match Command::sum_conversion(values, *to_unit, ctx) {
+                    Ok(command) => command.execute_with(ctx, options),
+                    Err(e) => CommandResult::error(error_message(&e)),
+                }
+            }
+            // the source value is the target converted back
+            Command::Invert(target, from_unit) => CommandResult::conversion(
+                target,
+                from_unit,
+                target.convert_to_with(from_unit, ctx),
+                options,
+            ),
+            Command::Chain(value, units) => {
+                let steps = std::iter::once(value.unit()).chain(units.iter().copied());
+                let result = ConversionChain::new(steps.collect()).and_then(|chain| {
+                    let value = value.value().ok_or_else(ConversionError::missing_value)?;
+                    chain.apply_with(value, ctx)
+                });
                 match result {
-                    Ok(v) => output.push_str(&v.to_string()),
-                    Err(e) => output.push_str(&e.to_string()),
+                    Ok(values) => CommandResult::ok(
+                        values
+                            .iter()
+                            .map(|v| v.format(options))
+                            .collect::<Vec<_>>()
+                            .join(" -> "),
+                    ),
+                    Err(e) => CommandResult::error(error_message(&e)),
                 }
             }
+            Command::Add(a, b, to_unit) => {
+                let result = a
+                    .add_with(b, ctx)
+                    .and_then(|sum| sum.convert_to_with(to_unit, ctx));
+                CommandResult::converted(result, options)
+            }
+            Command::Interpolate(t, start, end) => {
+                CommandResult::converted(interpolate_with(*t, start, end, ctx), options)
+            }
             Command::Units => {
-                output.push_str("Available units:\n");
-                let units = Unit::get_all_units();
-                for unit in units {
-                    output.push_str(&format!("{}\n", unit));
+                let sections: Vec<String> = UnitCategory::iter().map(Command::list_units).collect();
+                CommandResult::ok(sections.join("\n"))
+            }
+            Command::CategoryUnits(category) => CommandResult::ok(Command::list_units(*category)),
+            Command::CompatibleUnits(unit) => {
+                let mut output = format!("Units compatible with {}:\n", unit);
+                for unit in unit.compatible_units() {
+                    output.push_str(&format!("  {}\n", unit));
                 }
+                CommandResult::ok(output)
             }
-            Command::Help => output.push_str(
+            Command::InvalidateCache => {
+                ctx.with_cache(|cache| cache.invalidate());
+                CommandResult::ok("Cache invalidated.")
+            }
+            Command::Help => CommandResult::ok(
                 "Commands:\n\
-                - <value> <unit> -> <unit>: Convert a value to another unit.\n\
-                - units: List all available units.\n\
+                - <value> <unit> -> <unit>: Convert a value to another unit (or <value> <unit> to <unit>, <value> <unit> = ? <unit>).\n\
+                - <value> <unit> -> all: Convert a value to all units of its category.\n\
+                - <value> <unit> -> <unit> -> <unit>: Convert a value through several units, showing every step.\n\
+                - ? <unit> -> <value> <unit>: Find the value in <unit> that converts to the given value.\n\
+                - <value> <unit> + <value> <unit> -> <unit>: Add two values.\n\
+                - <percent>% between <value> <unit> and <value> <unit>: Interpolate between two values.\n\
+                - how many <unit> in a <unit>, what is <value> <unit> in <unit>: Ask for a conversion.\n\
+                - units [<category>]: List all available units, or those of one category.\n\
+                - compatible <unit>: List the units a unit can be converted to.\n\
+                - invalidate: Request fresh currency rates on the next conversion.\n\
+                - history: Show previous conversions, 'history clear' forgets them.\n\
+                - !<n>: Run the n-th history entry again.\n\
+                - <command>; <command>: Run several commands at once.\n\
+                - config: Show the effective configuration.\n\
+                - set-key <key>: Store the openexchangerates.org API key in the system keyring.\n\
                 - help: Show this help message.\n\
+                - about: Show the version and build information.\n\
                 - exit: Exit the program.",
             ),
-            _ => {}
-        };
+            Command::SetKey(key) => match store_api_key(key) {
+                Ok(()) => CommandResult::ok("API key stored in the keyring."),
+                Err(e) => CommandResult::error(e.to_string()),
+            },
+            Command::About => CommandResult::ok(Command::about()),
+            Command::Exit => CommandResult::exit(),
+            // the history and the configuration are kept by the interface
+            Command::History | Command::HistoryClear | Command::Recall(_) | Command::Config => {
+                CommandResult::ok("")
+            }
+            Command::Multi(commands) => Command::execute_all(commands, ctx, options),
+        }
+    }
 
-        output
+    /// Execute the commands until one exits, an error only fails the whole line if all of them failed.
+    fn execute_all(
+        commands: &[Result<Command, String>],
+        ctx: &ExecutionContext,
+        options: &FormatOptions,
+    ) -> CommandResult {
+        let mut outputs = Vec::new();
+        let mut all_failed = true;
+        for command in commands {
+            let result = match command {
+                Ok(command) => command.execute_with(ctx, options),
+                Err(e) => CommandResult::error(e.clone()),
+            };
+            if result.exit {
+                return CommandResult::exit();
+            }
+            all_failed &= result.is_error;
+            outputs.push(result.output);
+        }
+        CommandResult {
+            is_error: all_failed,
+            ..CommandResult::ok(outputs.join("\n"))
+        }
+    }
+
+    /// Execute the command and return the output as a JSON string.
+    /// Conversions report their input, output and error separately,
+    /// all other commands wrap their textual output.
+    pub fn execute_json(&self, ctx: &ExecutionContext) -> String {
+        self.to_json(ctx).to_string()
+    }
+
+    fn to_json(&self, ctx: &ExecutionContext) -> serde_json::Value {
+        match self {
+            Command::Convert(value, to_unit) | Command::Invert(value, to_unit) => match value
+                .convert_to_with(to_unit, ctx)
+            {
+                Ok(v) => json!({ "input": value.to_json(), "output": v.to_json(), "error": null }),
+                Err(e) => {
+                    json!({ "input": value.to_json(), "output": null, "error": error_message(&e) })
+                }
+            },
+            Command::ConvertCompound(values, to_unit) => {
+                match Command::sum_conversion(values, *to_unit, ctx) {
+                    Ok(command) => command.to_json(ctx),
+                    Err(e) => json!({ "error": error_message(&e) }),
+                }
+            }
+            Command::Units => {
+                let units: Vec<String> = Unit::all_units()
+                    .iter()
+                    .map(|unit| unit.to_string())
+                    .collect();
+                json!({ "units": units })
+            }
+            Command::Multi(commands) => commands
+                .iter()
+                .map(|command| match command {
+                    Ok(command) => command.to_json(ctx),
+                    Err(e) => json!({ "error": e }),
+                })
+                .collect(),
+            _ => json!({ "output": self.execute(ctx).output }),
+        }
     }
 }
 
 impl Command {
+    /// Conversion of the sum of the values of a `ConvertCompound`, summed when it is executed
+    /// so the exchange rates of the given context are used.
+    fn sum_conversion(
+        values: &[Value],
+        to_unit: Option<Unit>,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<Command> {
+        let sum = Value::sum_with(values, ctx)?;
+        Ok(match to_unit {
+            Some(to_unit) => Command::Convert(sum, to_unit),
+            None => Command::ConvertAll(sum),
+        })
+    }
+
+    /// Version, authors and build profile of the crate, with the git commit if known.
+    fn about() -> String {
+        let profile = if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        };
+        let mut output = format!(
+            "{} {}\nBuild: {}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            profile
+        );
+        if let Some(hash) = option_env!("CARGO_GIT_HASH") {
+            output.push_str(&format!(" ({})", hash));
+        }
+        let authors = env!("CARGO_PKG_AUTHORS");
+        if !authors.is_empty() {
+            output.push_str(&format!("\nAuthors: {}", authors.replace(':', ", ")));
+        }
+        output
+    }
+
+    /// List the units of a category with aligned names and abbreviations.
+    fn list_units(category: UnitCategory) -> String {
+        let units: Vec<Unit> = Unit::iter_by_category(category).collect();
+        let width = units
+            .iter()
+            .map(|unit| unit.full_name().chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut output = format!("{}:\n", category);
+        for unit in units {
+            let row = match unit {
+                // the codes are what users type, the names explain them
+                Unit::Currency(currency) => {
+                    format!("  {} - {}\n", unit.abbreviation(), currency.full_name())
+                }
+                _ => format!("  {:<width$}  {}\n", unit.full_name(), unit.abbreviation()),
+            };
+            output.push_str(&row);
+        }
+        output
+    }
+
+    /// Try parsing an addition command (<value> <unit> + <value> <unit> -> <unit>),
+    /// the target unit may also follow "to" or "= ?" as in conversions.
+    /// Returns `None` if the string does not look like an addition.
+    fn try_parse_addition(s: &str) -> Option<Result<Command, String>> {
+        let pattern = r"^(.+?)\s+\+\s+(.+?)(?:\s*->\s*|\s+to\s+|\s*=\s*\?\s*)(.+?)\s*$";
+        let re = Regex::new(pattern).unwrap();
+
+        let s = &normalize_input(s);
+        let caps = re.captures(s)?;
+        let parse = || -> Result<Command, String> {
+            let a = parse_value(&caps[1])?;
+            let b = parse_value(&caps[2])?;
+            let to_unit = parse_unit(&caps[3])?;
+            Ok(Command::Add(a, b, to_unit))
+        };
+        Some(parse())
+    }
+
+    /// Try parsing an interpolation command (<percent>% between <value> <unit> and <value> <unit>).
+    /// Returns `None` if the string does not look like an interpolation.
+    fn try_parse_interpolation(s: &str) -> Option<Result<Command, String>> {
+        let re = Regex::new(r"^(-?\d+(?:\.\d+)?)\s*%\s+between\s+(.+?)\s+and\s+(.+?)\s*$").unwrap();
+        let s = &normalize_input(s);
+        let caps = re.captures(s)?;
+        let parse = || -> Result<Command, String> {
+            let percent: f64 = caps[1]
+                .parse()
+                .map_err(|e: ParseFloatError| e.to_string())?;
+            let start: Value = caps[2]
+                .parse()
+                .map_err(|e: ConversionError| e.to_string())?;
+            let end: Value = caps[3]
+                .parse()
+                .map_err(|e: ConversionError| e.to_string())?;
+            Ok(Command::Interpolate(percent / 100.0, start, end))
+        };
+        Some(parse())
+    }
+
+    /// Try parsing a conversion through several units (<value> <unit> -> <unit> -> <unit>).
+    /// Returns `None` if the string has less than two arrows.
+    fn try_parse_chain(s: &str) -> Option<Result<Command, String>> {
+        let s = &normalize_input(s);
+        let parts: Vec<&str> = s.split("->").map(str::trim).collect();
+        // "? <unit> -> <value> <unit>" is an inversion
+        if parts.len() < 3 || s.starts_with('?') {
+            return None;
+        }
+        let parse = || -> Result<Command, String> {
+            let value: Value = parts[0]
+                .parse()
+                .map_err(|e: ConversionError| e.to_string())?;
+            let units = parts[1..]
+                .iter()
+                .map(|unit| parse_unit(unit))
+                .collect::<Result<Vec<Unit>, String>>()?;
+            Ok(Command::Chain(value, units))
+        };
+        Some(parse())
+    }
+
+    /// Try parsing a history recall command (!<n>).
+    /// Returns `None` if the string does not start with "!".
+    fn try_parse_recall(s: &str) -> Option<Result<Command, String>> {
+        let n = s.strip_prefix('!')?;
+        match n.parse() {
+            Ok(n) if n > 0 => Some(Ok(Command::Recall(n))),
+            _ => Some(Err(format!("Invalid history entry: {}", n))),
+        }
+    }
+
+    /// Try parsing a compatible units command (compatible <unit> or <unit> ->).
+    /// Returns `None` if the string does not look like one.
+    fn try_parse_compatible(s: &str) -> Option<Result<Command, String>> {
+        if let Some(unit) = s.strip_prefix("compatible ") {
+            return Some(parse_unit(unit).map(Command::CompatibleUnits));
+        }
+        let unit = normalize_input(s.strip_suffix("->")?);
+        // "<value> <unit> ->" is a conversion without a target unit
+        if Regex::new(r"^\s*-?\d").unwrap().is_match(&unit) {
+            return Some(Err(
+                "Missing target unit. Expression should be in the form <value> <unit> -> <unit>."
+                    .to_string(),
+            ));
+        }
+        Some(parse_unit(&unit).map(Command::CompatibleUnits))
+    }
+
+    /// Try parsing a conversion of a compound value (e.g. 5 ft 11 in -> cm).
+    /// Returns `None` if the value is not made of at least two <value> <unit> pairs.
+    fn try_parse_compound_conversion(s: &str) -> Option<Result<Command, String>> {
+        let re = Regex::new(r"^(.+?)(?:\s*->\s*|\s+to\s+)(.+?)\s*$").unwrap();
+        let s = &normalize_input(s);
+        let caps = re.captures(s)?;
+        let tokens: Vec<&str> = caps[1].split_whitespace().collect();
+        let is_compound = tokens.len() >= 4
+            && tokens.len().is_multiple_of(2)
+            && tokens
+                .iter()
+                .step_by(2)
+                .all(|token| token.parse::<f64>().is_ok());
+        if !is_compound {
+            return None;
+        }
+
+        let parse = || -> Result<Command, String> {
+            let values = Value::parse_compound(&caps[1]).map_err(|e| e.to_string())?;
+            if caps[2].trim() == "all" {
+                return Ok(Command::ConvertCompound(values, None));
+            }
+            Ok(Command::ConvertCompound(
+                values,
+                Some(parse_unit(&caps[2])?),
+            ))
+        };
+        Some(parse())
+    }
+
+    /// Try parsing a conversion asked in English, e.g. "how many feet in a mile?",
+    /// "convert 5 km to miles" or "what is 100 kg in pounds".
+    /// Returns `None` if the string is not one of these questions.
+    fn try_parse_natural_language(s: &str) -> Option<Result<Command, String>> {
+        let patterns = [
+            // how many <unit> (are) in a(n)/one/<value> <unit>
+            r"(?i)^how\s+many\s+(?P<to>.+?)\s+(?:are\s+)?in\s+(?:an?\s+|one\s+|(?P<value>-?\d+(?:\.\d+)?)\s*)?(?P<from>.+?)\s*\??$",
+            // convert <value> <unit> to/into/in <unit>
+            r"(?i)^convert\s+(?P<value>-?\d+(?:\.\d+)?)\s*(?P<from>.+?)\s+(?:to|into|in)\s+(?P<to>.+?)\s*\??$",
+            // what is/what's <value> <unit> in <unit>
+            r"(?i)^what(?:\s+is|'s)\s+(?P<value>-?\d+(?:\.\d+)?)\s*(?P<from>.+?)\s+in\s+(?P<to>.+?)\s*\??$",
+        ];
+        let s = &normalize_input(s);
+        let caps = patterns
+            .iter()
+            .find_map(|pattern| Regex::new(pattern).unwrap().captures(s))?;
+        let parse = || -> Result<Command, String> {
+            let value: f64 = caps
+                .name("value")
+                .map_or(Ok(1.0), |value| value.as_str().parse())
+                .map_err(|e: ParseFloatError| e.to_string())?;
+            let v = Value::new(value, parse_unit(&caps["from"])?);
+            if &caps["to"] == "all" {
+                return Ok(Command::ConvertAll(v));
+            }
+            Ok(Command::Convert(v, parse_unit(&caps["to"])?))
+        };
+        Some(parse())
+    }
+
     /// Try parsing a conversion command from a string.
     fn try_parse_conversion(s: &str) -> Result<Command, String> {
-        // define regex pattern (<value> <unit> -> <unit>)
-        let pattern = r"(\d+(?:\.\d+)?)\s(.+)\s->\s(.+)";
-        let re = Regex::new(pattern).unwrap();
+        let s = &normalize_input(s);
+        // ? <unit> -> <value> <unit>
+        let invert = Regex::new(r"^\?\s*(.+?)\s*->\s*(-?\d+(?:\.\d+)?)\s+(.+)").unwrap();
+        if let Some(caps) = invert.captures(s) {
+            let from_unit = parse_unit(&caps[1])?;
+            let value: f64 = caps[2]
+                .parse()
+                .map_err(|e: ParseFloatError| e.to_string())?;
+            let target = Value::new(value, parse_unit(&caps[3])?);
+            return Ok(Command::Invert(target, from_unit));
+        }
 
-        match re.captures(s) {
-            Some(caps) => {
-                let value: f64 = caps[1]
-                    .parse()
-                    .map_err(|e: ParseFloatError| e.to_string())?;
-                let from_unit = caps[2].parse()?;
-                let to_unit = caps[3].parse()?;
+        let patterns = [
+            // <value> <unit> -> <unit> or <value> <unit> to <unit>,
+            // spaces around "->" are optional, "to" needs them to not split unit names
+            r"(-?(?:0[xX][0-9a-fA-F]+|0[bB][01]+|0[oO][0-7]+|\d+(?:\.\d+)?(?:[eE][+-]?\d+)?))\s*(.+?)(?:\s*->\s*|\s+to\s+)(.+?)\s*$",
+            // <value> <unit> = ? <unit>
+            r"(-?(?:0[xX][0-9a-fA-F]+|0[bB][01]+|0[oO][0-7]+|\d+(?:\.\d+)?(?:[eE][+-]?\d+)?))\s*(.+?)\s*=\s*\?\s*(.+?)\s*$",
+        ];
+        let captures = patterns
+            .iter()
+            .find_map(|pattern| Regex::new(pattern).unwrap().captures(s));
 
+        match captures {
+            Some(caps) => {
+                // hexadecimal, binary and octal values are recognized by their prefix
+                let value = NumeralSystem::detect(&caps[1])
+                    .parse(&caps[1])
+                    .map_err(|e| e.to_string())?;
+                let from_unit = parse_unit(&caps[2])?;
                 let v = Value::new(value, from_unit);
+                if caps[3].trim() == "all" {
+                    return Ok(Command::ConvertAll(v));
+                }
+
+                let to_unit = parse_unit(&caps[3])?;
                 Ok(Command::Convert(v, to_unit))
             }
             None => Err(
@@ -77,16 +627,154 @@ impl Command {
     }
 }
 
+/// Parse a value of an expression, the space between number and unit is optional, e.g. "1m".
+fn parse_value(s: &str) -> Result<Value, String> {
+    let re = Regex::new(
+        r"^(-?(?:0[xX][0-9a-fA-F]+|0[bB][01]+|0[oO][0-7]+|\d+(?:\.\d+)?(?:[eE][+-]?\d+)?))\s*(.+?)\s*$",
+    )
+    .unwrap();
+    let caps = re
+        .captures(s.trim())
+        .ok_or_else(|| format!("Expected <value> <unit>, got: {}", s.trim()))?;
+    let value = NumeralSystem::detect(&caps[1])
+        .parse(&caps[1])
+        .map_err(|e| e.to_string())?;
+    Ok(Value::new(value, parse_unit(&caps[2])?))
+}
+
+/// Parse a unit of an expression, listing the candidates of an ambiguous name.
+fn parse_unit(s: &str) -> Result<Unit, String> {
+    Unit::parse_unambiguous(s.trim()).map_err(|e| e.message().to_string())
+}
+
+/// Replace typographic characters, as pasted from word processors or web pages,
+/// with their ASCII counterparts, e.g. the minus sign in "−10 °C".
+fn normalize_input(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\u{2212}' | '\u{2013}' | '\u{2014}' => '-',
+            '\u{00D7}' => '*',
+            '\u{00F7}' => '/',
+            c => c,
+        })
+        .collect()
+}
+
+/// Process one command per line, e.g. from a file or stdin.
+/// Empty lines and lines starting with `#` are skipped, processing stops at `exit`.
+pub fn process_batch(reader: impl BufRead, ctx: &ExecutionContext) -> Vec<Result<String, String>> {
+    execute_batch(reader, ctx)
+        .into_iter()
+        .map(|result| {
+            if result.is_error {
+                Err(result.output)
+            } else {
+                Ok(result.output)
+            }
+        })
+        .collect()
+}
+
+/// Like `process_batch`, but returns the full result of every command.
+pub fn execute_batch(reader: impl BufRead, ctx: &ExecutionContext) -> Vec<CommandResult> {
+    let mut results = Vec::new();
+    for line in reader.lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                results.push(CommandResult::error(e.to_string()));
+                continue;
+            }
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let result = match line.parse::<Command>() {
+            Ok(command) => command.execute(ctx),
+            Err(e) => CommandResult::error(e),
+        };
+        if result.exit {
+            break;
+        }
+        results.push(result);
+    }
+    results
+}
+
+/// Writes command results as CSV lines, starting with `CSV_HEADER`.
+pub struct CsvFormatter<W: Write> {
+    writer: W,
+    header_written: bool,
+}
+
+impl<W: Write> CsvFormatter<W> {
+    pub fn new(writer: W) -> Self {
+        CsvFormatter {
+            writer,
+            header_written: false,
+        }
+    }
+
+    /// Write one result, preceded by the header on the first call.
+    pub fn write(&mut self, result: &CommandResult) -> io::Result<()> {
+        if !self.header_written {
+            writeln!(self.writer, "{}", CSV_HEADER)?;
+            self.header_written = true;
+        }
+        writeln!(self.writer, "{}", result.to_csv_line())
+    }
+
+    /// The wrapped writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
 impl FromStr for Command {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s
+            .split(';')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect();
+        if parts.len() > 1 {
+            return Ok(Command::Multi(parts.into_iter().map(str::parse).collect()));
+        }
+        let s = parts.first().copied().unwrap_or(s);
+
         // try to parse a conversion command seperate from the other commands
-        let conversion_result = Command::try_parse_conversion(s);
+        let conversion_result = Command::try_parse_addition(s)
+            .or_else(|| Command::try_parse_interpolation(s))
+            .or_else(|| Command::try_parse_chain(s))
+            .or_else(|| Command::try_parse_recall(s))
+            .or_else(|| Command::try_parse_compatible(s))
+            .or_else(|| Command::try_parse_compound_conversion(s))
+            .or_else(|| Command::try_parse_natural_language(s))
+            .unwrap_or_else(|| Command::try_parse_conversion(s));
 
         match s {
             "units" => Ok(Command::Units),
+            _ if s.starts_with("units ") => s["units ".len()..].parse().map(Command::CategoryUnits),
+            "invalidate" => Ok(Command::InvalidateCache),
+            "history" => Ok(Command::History),
+            "history clear" => Ok(Command::HistoryClear),
+            "config" => Ok(Command::Config),
+            // the key must be separated from the keyword, "set-keyfoo" is not a command
+            _ if s
+                .strip_prefix("set-key")
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace)) =>
+            {
+                match s["set-key".len()..].trim() {
+                    "" => Err("Usage: set-key <key>".to_string()),
+                    key => Ok(Command::SetKey(key.to_string())),
+                }
+            }
             "help" => Ok(Command::Help),
+            "about" | "version" | "--version" => Ok(Command::About),
             "exit" => Ok(Command::Exit),
             _ => conversion_result,
         }
@@ -95,9 +783,58 @@ impl FromStr for Command {
 
 #[cfg(test)]
 mod tests {
-    use crate::core::units::LengthUnit;
+    use crate::core::units::{
+        ConversionErrorKind, CurrencyUnit, DataUnit, LengthUnit, MassUnit, TemperatureUnit,
+    };
 
     use super::*;
+    use crate::core::currency::{CacheConfig, ConversionCache};
+    use crate::core::providers::MockExchangeRateProvider;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_auth_error_message() {
+        let error = ConversionError::with_source(
+            ConversionErrorKind::NetworkError,
+            "API error: 401 Unauthorized",
+            APIError::from_status(reqwest::StatusCode::UNAUTHORIZED),
+        );
+        let result = CommandResult::converted(Err(error), &FormatOptions::default());
+        assert!(result.is_error);
+        assert_eq!(result.output, AUTH_ERROR_MESSAGE);
+
+        let error = ConversionError::with_source(
+            ConversionErrorKind::NetworkError,
+            "API error: 429 Too Many Requests",
+            APIError::from_status(reqwest::StatusCode::TOO_MANY_REQUESTS),
+        );
+        assert_eq!(
+            error_message(&error),
+            "Conversion error: API error: 429 Too Many Requests"
+        );
+    }
+
+    #[test]
+    fn test_normalize_input() {
+        assert_eq!(normalize_input("\u{2212}10 °C"), "-10 °C");
+        assert_eq!(normalize_input("\u{2013}1 m \u{2014}> km"), "-1 m -> km");
+        assert_eq!(normalize_input("2 \u{00D7} 3 \u{00F7} 4"), "2 * 3 / 4");
+        assert_eq!(normalize_input("100 µm -> m"), "100 µm -> m");
+    }
+
+    #[test]
+    fn test_unicode_minus() {
+        assert!("-10 °C -> °F".parse::<Command>().is_ok());
+        assert_eq!(
+            "\u{2212}10 °C -> °F".parse::<Command>(),
+            "-10 °C -> °F".parse::<Command>()
+        );
+        assert_eq!(
+            "\u{2013}10 °C to °F".parse::<Command>(),
+            "-10 °C -> °F".parse::<Command>()
+        );
+    }
 
     #[test]
     fn test_command_from_str() {
@@ -123,7 +860,641 @@ mod tests {
         assert!(command.is_ok());
         assert_eq!(command.unwrap(), Command::Exit);
 
+        let command = "invalidate".parse::<Command>();
+        assert_eq!(command.unwrap(), Command::InvalidateCache);
+
         let command = "invalid".parse::<Command>();
         assert!(command.is_err());
     }
+
+    #[test]
+    fn test_command_from_str_negative_value() {
+        let command = "-40 °C -> °F".parse::<Command>();
+        assert_eq!(
+            command.unwrap(),
+            Command::Convert(
+                Value::new(-40.0, Unit::Temperature(TemperatureUnit::Celsius)),
+                Unit::Temperature(TemperatureUnit::Fahrenheit)
+            )
+        );
+
+        let command = "-50.5 m -> ft".parse::<Command>();
+        assert_eq!(
+            command.unwrap(),
+            Command::Convert(
+                Value::new(-50.5, Unit::Length(LengthUnit::Meter)),
+                Unit::Length(LengthUnit::Foot)
+            )
+        );
+    }
+
+    #[test]
+    fn test_execute_below_absolute_zero() {
+        let command = "-274 °C -> K".parse::<Command>().unwrap();
+        assert!(command
+            .execute(ExecutionContext::global())
+            .output
+            .starts_with("Conversion error"));
+
+        let command = "-273.15 °C -> K".parse::<Command>().unwrap();
+        assert!(command
+            .execute(ExecutionContext::global())
+            .output
+            .starts_with("0 "));
+    }
+
+    #[test]
+    fn test_command_from_str_scientific_notation() {
+        let command = "1.5e3 m -> km".parse::<Command>();
+        assert_eq!(
+            command.unwrap(),
+            Command::Convert(
+                Value::new(1500.0, Unit::Length(LengthUnit::Meter)),
+                Unit::Length(LengthUnit::Kilometer)
+            )
+        );
+
+        let command = "6.674e-11 m -> cm".parse::<Command>();
+        assert_eq!(
+            command.unwrap(),
+            Command::Convert(
+                Value::new(6.674e-11, Unit::Length(LengthUnit::Meter)),
+                Unit::Length(LengthUnit::Centimeter)
+            )
+        );
+
+        let command = "1E2 kg -> g".parse::<Command>().unwrap();
+        assert_eq!(
+            command.execute(ExecutionContext::global()).output,
+            "100000 g"
+        );
+    }
+
+    #[test]
+    fn test_execute_json() {
+        let command = "1 m -> km".parse::<Command>().unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&command.execute_json(ExecutionContext::global())).unwrap();
+        assert_eq!(json["input"]["value"], 1.0);
+        assert_eq!(json["input"]["unit"], "meter (m)");
+        assert_eq!(json["output"]["value"], 0.001);
+        assert_eq!(json["output"]["unit"], "kilometer (km)");
+        assert!(json["error"].is_null());
+
+        let command = "1 m -> kg".parse::<Command>().unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&command.execute_json(ExecutionContext::global())).unwrap();
+        assert!(json["output"].is_null());
+        assert!(json["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Conversion error"));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&Command::Units.execute_json(ExecutionContext::global())).unwrap();
+        assert!(json["units"].as_array().unwrap().len() > 1);
+    }
+
+    #[test]
+    fn test_execute_units_grouped_by_category() {
+        let output = Command::Units.execute(ExecutionContext::global()).output;
+        assert!(output.starts_with("Length:\n  meter "));
+        assert!(output.contains("\nMass:\n  kilogram "));
+        assert!(output.contains("\nCurrency:\n  USD - US Dollar\n"));
+        for unit in Unit::get_all_units() {
+            if unit.category() == UnitCategory::Currency {
+                continue;
+            }
+            let row = format!("  {}", unit.full_name());
+            assert!(
+                output
+                    .lines()
+                    .any(|line| line.starts_with(&row) && line.ends_with(unit.abbreviation())),
+                "{} missing",
+                unit
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_category_units() {
+        let command = "units mass".parse::<Command>().unwrap();
+        assert_eq!(command, Command::CategoryUnits(UnitCategory::Mass));
+        let output = command.execute(ExecutionContext::global()).output;
+        assert!(output.starts_with("Mass:\n"));
+        assert!(!output.contains("meter"));
+        assert!("units foo".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_command_convert_all() {
+        let command = "1 m -> all".parse::<Command>().unwrap();
+        assert_eq!(
+            command,
+            Command::ConvertAll(Value::new(1.0, Unit::Length(LengthUnit::Meter)))
+        );
+        let output = command.execute(ExecutionContext::global()).output;
+        assert!(output.contains("kilometer"));
+        assert!(output.contains("(km)"));
+        assert!(output.contains("0.001"));
+    }
+
+    #[test]
+    fn test_command_compatible_units() {
+        let meter = Unit::Length(LengthUnit::Meter);
+        assert_eq!(
+            "compatible m".parse::<Command>(),
+            Ok(Command::CompatibleUnits(meter))
+        );
+        assert_eq!(
+            "m ->".parse::<Command>(),
+            Ok(Command::CompatibleUnits(meter))
+        );
+        assert!("compatible foo".parse::<Command>().is_err());
+        assert_eq!(
+            "100 m ->".parse::<Command>(),
+            Err(
+                "Missing target unit. Expression should be in the form <value> <unit> -> <unit>."
+                    .to_string()
+            )
+        );
+        assert!("\u{2212}5 °C ->".parse::<Command>().is_err());
+
+        let output = Command::CompatibleUnits(meter)
+            .execute(ExecutionContext::global())
+            .output;
+        assert!(output.contains("  kilometer (km)\n"));
+        assert!(!output.contains("kilogram"));
+    }
+
+    #[test]
+    fn test_command_from_str_to_keyword() {
+        assert_eq!(
+            "100 m to km".parse::<Command>(),
+            "100 m -> km".parse::<Command>()
+        );
+
+        let command = "100 ton to kg".parse::<Command>();
+        assert_eq!(
+            command.unwrap(),
+            Command::Convert(
+                Value::new(100.0, Unit::Mass(MassUnit::MetricTon)),
+                Unit::Mass(MassUnit::Kilogram)
+            )
+        );
+
+        let command = "1 kg to ton".parse::<Command>();
+        assert_eq!(
+            command.unwrap(),
+            Command::Convert(
+                Value::new(1.0, Unit::Mass(MassUnit::Kilogram)),
+                Unit::Mass(MassUnit::MetricTon)
+            )
+        );
+    }
+
+    #[test]
+    fn test_plural_unit_names() {
+        assert_eq!(
+            "100 meters -> feet".parse::<Command>(),
+            Ok(Command::Convert(
+                Value::new(100.0, Unit::Length(LengthUnit::Meter)),
+                Unit::Length(LengthUnit::Foot)
+            ))
+        );
+        assert_eq!(
+            "5 pounds -> kilograms".parse::<Command>(),
+            Ok(Command::Convert(
+                Value::new(5.0, Unit::Mass(MassUnit::Pound)),
+                Unit::Mass(MassUnit::Kilogram)
+            ))
+        );
+        assert_eq!(
+            "2 inches -> cm".parse::<Command>(),
+            Ok(Command::Convert(
+                Value::new(2.0, Unit::Length(LengthUnit::Inch)),
+                Unit::Length(LengthUnit::Centimeter)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_about() {
+        for input in ["about", "version", "--version"] {
+            assert_eq!(input.parse::<Command>(), Ok(Command::About));
+        }
+        let result = Command::from_str("about")
+            .unwrap()
+            .execute(ExecutionContext::global());
+        assert!(!result.is_error);
+        assert!(result.output.contains(env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn test_command_result() {
+        assert!(Command::Exit.execute(ExecutionContext::global()).exit);
+        assert!(!Command::Help.execute(ExecutionContext::global()).is_error);
+        assert!(!Command::Help.execute(ExecutionContext::global()).exit);
+
+        let command = "1 m -> kg".parse::<Command>().unwrap();
+        assert!(command.execute(ExecutionContext::global()).is_error);
+        let command = "1 m -> cm".parse::<Command>().unwrap();
+        assert!(!command.execute(ExecutionContext::global()).is_error);
+    }
+
+    #[test]
+    fn test_command_history() {
+        assert_eq!("history".parse::<Command>(), Ok(Command::History));
+        assert_eq!(
+            "history clear".parse::<Command>(),
+            Ok(Command::HistoryClear)
+        );
+        assert_eq!("!3".parse::<Command>(), Ok(Command::Recall(3)));
+        assert!("!0".parse::<Command>().is_err());
+        assert!("!x".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_command_config() {
+        assert_eq!("config".parse::<Command>(), Ok(Command::Config));
+    }
+
+    #[test]
+    fn test_command_from_str_compact() {
+        let spaced = "100 m -> km".parse::<Command>();
+        assert_eq!("100m->km".parse::<Command>(), spaced);
+        assert_eq!("100m ->km".parse::<Command>(), spaced);
+        assert_eq!("100 m->km".parse::<Command>(), spaced);
+
+        assert_eq!(
+            "1.5e3km->m".parse::<Command>().unwrap(),
+            Command::Convert(
+                Value::new(1500.0, Unit::Length(LengthUnit::Kilometer)),
+                Unit::Length(LengthUnit::Meter)
+            )
+        );
+        assert_eq!(
+            "100 USD->EUR".parse::<Command>().unwrap(),
+            Command::Convert(
+                Value::new(100.0, Unit::Currency(CurrencyUnit::USD)),
+                Unit::Currency(CurrencyUnit::EUR)
+            )
+        );
+        assert_eq!(
+            "50lb -> kg".parse::<Command>().unwrap(),
+            Command::Convert(
+                Value::new(50.0, Unit::Mass(MassUnit::Pound)),
+                Unit::Mass(MassUnit::Kilogram)
+            )
+        );
+    }
+
+    #[test]
+    fn test_command_from_str_question_mark() {
+        let arrow = "100 m -> km".parse::<Command>();
+        assert_eq!("100 m = ? km".parse::<Command>(), arrow);
+        assert_eq!("100 m =? km".parse::<Command>(), arrow);
+        assert_eq!("100 m=?km".parse::<Command>(), arrow);
+        assert!("100 m = km".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_command_add() {
+        let command = "5 m + 100 cm -> m".parse::<Command>().unwrap();
+        assert_eq!(
+            command,
+            Command::Add(
+                Value::new(5.0, Unit::Length(LengthUnit::Meter)),
+                Value::new(100.0, Unit::Length(LengthUnit::Centimeter)),
+                Unit::Length(LengthUnit::Meter)
+            )
+        );
+        assert_eq!(command.execute(ExecutionContext::global()).output, "6 m");
+
+        let command = "5 m + 3 ft = ? m".parse::<Command>().unwrap();
+        assert_eq!(
+            command,
+            Command::Add(
+                Value::new(5.0, Unit::Length(LengthUnit::Meter)),
+                Value::new(3.0, Unit::Length(LengthUnit::Foot)),
+                Unit::Length(LengthUnit::Meter)
+            )
+        );
+        let command = "1m + 2cm -> mm".parse::<Command>().unwrap();
+        assert_eq!(
+            command,
+            Command::Add(
+                Value::new(1.0, Unit::Length(LengthUnit::Meter)),
+                Value::new(2.0, Unit::Length(LengthUnit::Centimeter)),
+                Unit::Length(LengthUnit::Millimeter)
+            )
+        );
+        assert_eq!(
+            command.execute(ExecutionContext::global()).output,
+            "1020 mm"
+        );
+        assert_eq!(
+            "\u{2212}1 m + 2 m to m".parse::<Command>(),
+            Ok(Command::Add(
+                Value::new(-1.0, Unit::Length(LengthUnit::Meter)),
+                Value::new(2.0, Unit::Length(LengthUnit::Meter)),
+                Unit::Length(LengthUnit::Meter)
+            ))
+        );
+
+        let command = Command::Add(
+            Value::new(5.0, Unit::Length(LengthUnit::Meter)),
+            Value::new(3.0, Unit::Mass(MassUnit::Kilogram)),
+            Unit::Length(LengthUnit::Meter),
+        );
+        assert!(command
+            .execute(ExecutionContext::global())
+            .output
+            .starts_with("Conversion error"));
+    }
+
+    #[test]
+    fn test_execute_with_context() {
+        let provider = MockExchangeRateProvider {
+            rates: HashMap::from([(CurrencyUnit::USD, 1.0), (CurrencyUnit::EUR, 2.0)]),
+        };
+        let ctx = ExecutionContext::new(ConversionCache::with_config(CacheConfig {
+            db_path: ":memory:".into(),
+            provider: Arc::new(provider),
+            ..Default::default()
+        }));
+        let command = Command::from_str("10 USD -> EUR").unwrap();
+        assert_eq!(command.execute(&ctx).output, "20 EUR");
+        let command = Command::from_str("10 USD + 10 EUR -> EUR").unwrap();
+        assert_eq!(command.execute(&ctx).output, "30 EUR");
+        // compound values are summed with the rates of the context they are executed with
+        let command = Command::from_str("10 USD 5 EUR -> EUR").unwrap();
+        assert_eq!(command.execute(&ctx).output, "25 EUR");
+
+        // the global context still uses its own rates
+        let command = Command::from_str("10 USD -> EUR").unwrap();
+        assert_eq!(command.execute(ExecutionContext::global()).output, "9 EUR");
+
+        assert!(!Command::InvalidateCache.execute(&ctx).is_error);
+        assert!(ctx.with_cache(|cache| cache.is_stale()));
+    }
+
+    #[test]
+    fn test_csv_line() {
+        let command = Command::from_str("1.5 km -> m").unwrap();
+        let result = command.execute(ExecutionContext::global());
+        assert_eq!(result.to_csv_line(), "1.5,km,1500,m,");
+
+        let result = CommandResult::error("Invalid unit: x, y");
+        assert_eq!(result.to_csv_line(), ",,,,\"Invalid unit: x, y\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(
+            CommandResult::ok("Cache invalidated.").to_csv_line(),
+            ",,,,"
+        );
+    }
+
+    #[test]
+    fn test_command_multi() {
+        let command = "100 m -> km; help".parse::<Command>().unwrap();
+        match &command {
+            Command::Multi(commands) => {
+                assert_eq!(commands.len(), 2);
+                assert!(matches!(commands[0], Ok(Command::Convert(..))));
+                assert_eq!(commands[1], Ok(Command::Help));
+            }
+            _ => panic!("expected Command::Multi, got {:?}", command),
+        }
+        // a trailing separator does not make a multi command
+        assert!(matches!(
+            "100 m -> km;".parse::<Command>(),
+            Ok(Command::Convert(..))
+        ));
+
+        let command = "100 m -> km; 1 m -> xyz; 1 kg -> g"
+            .parse::<Command>()
+            .unwrap();
+        let result = command.execute(ExecutionContext::global());
+        assert!(!result.is_error);
+        let lines: Vec<&str> = result.output.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "0.1 km");
+        assert!(lines[1].starts_with("Invalid unit"));
+        assert_eq!(lines[2], "1000 g");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&command.execute_json(ExecutionContext::global())).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert!(json[1]["error"].is_string());
+    }
+
+    #[test]
+    fn test_compound_conversion() {
+        let command = "5 ft 11 in -> cm".parse::<Command>().unwrap();
+        let foot = Unit::Length(LengthUnit::Foot);
+        let inch = Unit::Length(LengthUnit::Inch);
+        assert_eq!(
+            command,
+            Command::ConvertCompound(
+                vec![Value::new(5.0, foot), Value::new(11.0, inch)],
+                Some(Unit::Length(LengthUnit::Centimeter))
+            )
+        );
+        assert_eq!(
+            command.execute(ExecutionContext::global()).output,
+            "180.34 cm"
+        );
+        assert!(matches!(
+            "2 m 30 cm to all".parse::<Command>(),
+            Ok(Command::ConvertCompound(_, None))
+        ));
+        assert!("5 ft 11 kg -> cm".parse::<Command>().is_err());
+
+        // simple conversions are left to the regular parser
+        assert_eq!(Command::try_parse_compound_conversion("5 ft -> cm"), None);
+        assert_eq!(
+            Command::try_parse_compound_conversion("1 bit per second -> kbps"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_prefixed_values() {
+        let command = "0x100 B -> KiB".parse::<Command>().unwrap();
+        assert_eq!(
+            command,
+            Command::Convert(
+                Value::new(256.0, Unit::Data(DataUnit::Byte)),
+                Unit::Data(DataUnit::Kibibyte)
+            )
+        );
+        let result = command.execute(ExecutionContext::global());
+        assert_eq!(
+            result.conversion.unwrap().output,
+            Some(Value::new(0.25, Unit::Data(DataUnit::Kibibyte)))
+        );
+
+        assert_eq!(
+            "0b1010 m to cm".parse::<Command>(),
+            "10 m to cm".parse::<Command>()
+        );
+        assert_eq!(
+            "0o17 m = ? cm".parse::<Command>(),
+            "15 m = ? cm".parse::<Command>()
+        );
+
+        let options = FormatOptions {
+            base: NumeralSystem::Hexadecimal,
+            ..Default::default()
+        };
+        let result = "1 KiB -> B"
+            .parse::<Command>()
+            .unwrap()
+            .execute_with(ExecutionContext::global(), &options);
+        assert_eq!(result.output, "0x400 B");
+    }
+
+    #[test]
+    fn test_natural_language() {
+        let meter = Unit::Length(LengthUnit::Meter);
+        let foot = Unit::Length(LengthUnit::Foot);
+        assert_eq!(
+            "how many feet in a meter".parse(),
+            Ok(Command::Convert(Value::new(1.0, meter), foot))
+        );
+        assert_eq!(
+            "How many feet are in 3 meters?".parse(),
+            Ok(Command::Convert(Value::new(3.0, meter), foot))
+        );
+        assert_eq!(
+            "what is 100 kg in pounds".parse(),
+            Ok(Command::Convert(
+                Value::new(100.0, Unit::Mass(MassUnit::Kilogram)),
+                Unit::Mass(MassUnit::Pound)
+            ))
+        );
+        assert_eq!(
+            "convert 5 m to ft".parse(),
+            Ok(Command::Convert(Value::new(5.0, meter), foot))
+        );
+        assert_eq!(
+            "what's 2 m in all?".parse(),
+            Ok(Command::ConvertAll(Value::new(2.0, meter)))
+        );
+
+        let result = "how many feet in a mile?"
+            .parse::<Command>()
+            .unwrap()
+            .execute(ExecutionContext::global());
+        assert!(result
+            .conversion
+            .unwrap()
+            .output
+            .unwrap()
+            .approx_eq(&Value::new(5280.0, foot))
+            .unwrap());
+
+        assert!("how many feet in a fortnight".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_chain() {
+        let kg = Unit::Mass(MassUnit::Kilogram);
+        let lb = Unit::Mass(MassUnit::Pound);
+        let oz = Unit::Mass(MassUnit::Ounce);
+        let command = "1 kg -> lb -> oz".parse::<Command>().unwrap();
+        assert_eq!(command, Command::Chain(Value::new(1.0, kg), vec![lb, oz]));
+
+        let options = FormatOptions {
+            precision: Some(3),
+            ..Default::default()
+        };
+        let result = command.execute_with(ExecutionContext::global(), &options);
+        assert_eq!(result.output, "1.000 kg -> 2.205 lb -> 35.274 oz");
+
+        let result = "1 kg -> lb -> m"
+            .parse::<Command>()
+            .unwrap()
+            .execute(ExecutionContext::global());
+        assert!(result.is_error);
+        assert!("1 kg -> lb -> xyz".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_invert() {
+        let command = "? m -> 1 km".parse::<Command>().unwrap();
+        assert_eq!(
+            command,
+            Command::Invert(
+                Value::new(1.0, Unit::Length(LengthUnit::Kilometer)),
+                Unit::Length(LengthUnit::Meter)
+            )
+        );
+        let result = command.execute(ExecutionContext::global());
+        assert_eq!(
+            result.conversion.unwrap().output,
+            Some(Value::new(1000.0, Unit::Length(LengthUnit::Meter)))
+        );
+
+        let command = "? °F -> 100 °C".parse::<Command>().unwrap();
+        let result = command.execute(ExecutionContext::global());
+        let fahrenheit = result.conversion.unwrap().output.unwrap();
+        assert_eq!(
+            fahrenheit.unit(),
+            Unit::Temperature(TemperatureUnit::Fahrenheit)
+        );
+        assert!((fahrenheit.value().unwrap() - 212.0).abs() < 1e-9);
+
+        assert!(
+            "? m -> 1 kg"
+                .parse::<Command>()
+                .unwrap()
+                .execute(ExecutionContext::global())
+                .is_error
+        );
+        assert!("? foo -> 1 km".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let celsius = |value| Value::new(value, Unit::Temperature(TemperatureUnit::Celsius));
+        let command = "50% between 0 °C and 100 °C".parse::<Command>().unwrap();
+        assert_eq!(
+            command,
+            Command::Interpolate(0.5, celsius(0.0), celsius(100.0))
+        );
+        assert_eq!(command.execute(ExecutionContext::global()).output, "50 °C");
+
+        let result = "150% between 0 °C and 100 °C"
+            .parse::<Command>()
+            .unwrap()
+            .execute(ExecutionContext::global());
+        assert!(result.is_error);
+        assert!(
+            "50% between 0 °C and 1 m"
+                .parse::<Command>()
+                .unwrap()
+                .execute(ExecutionContext::global())
+                .is_error
+        );
+        assert!("50% between 0 °C and".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_set_key() {
+        assert_eq!(
+            "set-key abc123".parse::<Command>(),
+            Ok(Command::SetKey("abc123".to_string()))
+        );
+        assert!("set-key".parse::<Command>().is_err());
+        assert!("set-key  ".parse::<Command>().is_err());
+        assert!("set-keyfoo".parse::<Command>().is_err());
+
+        #[cfg(not(feature = "keyring"))]
+        assert!(
+            Command::SetKey("abc123".to_string())
+                .execute(ExecutionContext::global())
+                .is_error
+        );
+    }
 }
@@ -0,0 +1,82 @@
+use super::currency::ConversionCache;
+use once_cell::sync::Lazy;
+use std::sync::Arc;
+#[cfg(not(feature = "async"))]
+use std::sync::Mutex;
+#[cfg(feature = "async")]
+use tokio::sync::Mutex;
+
+#[cfg(not(test))]
+static GLOBAL: Lazy<ExecutionContext> = Lazy::new(ExecutionContext::default);
+
+/// Unit tests convert currencies with fixed rates, without network access or a database file.
+#[cfg(test)]
+static GLOBAL: Lazy<ExecutionContext> = Lazy::new(|| {
+    ExecutionContext::new(ConversionCache::with_config(super::currency::CacheConfig {
+        db_path: ":memory:".into(),
+        provider: Arc::new(super::providers::MockExchangeRateProvider::default()),
+        ..Default::default()
+    }))
+});
+
+/// State commands are executed with, passed in so callers decide which currency cache is used.
+#[derive(Clone)]
+pub struct ExecutionContext {
+    /// Exchange rates used for currency conversions
+    pub cache: Arc<Mutex<ConversionCache>>,
+}
+
+impl ExecutionContext {
+    /// Create a context converting currencies with the given cache.
+    pub fn new(cache: ConversionCache) -> Self {
+        ExecutionContext {
+            cache: Arc::new(Mutex::new(cache)),
+        }
+    }
+
+    /// Context shared by the program and the conversions that take none, e.g. `Value::convert_to`.
+    pub fn global() -> &'static ExecutionContext {
+        &GLOBAL
+    }
+
+    /// Run `f` with exclusive access to the currency cache.
+    /// With the `async` feature this may also be called from within a tokio runtime.
+    pub(crate) fn with_cache<T: Send>(
+        &self,
+        f: impl FnOnce(&mut ConversionCache) -> T + Send,
+    ) -> T {
+        #[cfg(not(feature = "async"))]
+        {
+            f(&mut self.cache.lock().unwrap())
+        }
+        #[cfg(feature = "async")]
+        {
+            use tokio::runtime::{Handle, RuntimeFlavor};
+
+            let run = || f(&mut self.cache.blocking_lock());
+            match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+                Ok(RuntimeFlavor::MultiThread) => tokio::task::block_in_place(run),
+                // block_in_place panics on a current-thread runtime, block another thread instead
+                Ok(_) => std::thread::scope(|scope| {
+                    scope
+                        .spawn(run)
+                        .join()
+                        .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                }),
+                Err(_) => run(),
+            }
+        }
+    }
+}
+
+impl Default for ExecutionContext {
+    fn default() -> Self {
+        ExecutionContext::new(ConversionCache::new())
+    }
+}
+
+impl std::fmt::Debug for ExecutionContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionContext").finish_non_exhaustive()
+    }
+}
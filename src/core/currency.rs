@@ -1,44 +1,122 @@
+use super::providers::{ExchangeRateProvider, OpenExchangeRatesProvider};
 use super::units::CurrencyUnit;
 use chrono::{DateTime, TimeDelta, Utc};
 use reqwest;
-use rusqlite::{Connection, Result};
-use serde_json::Value;
-use std::{collections::HashMap, fmt::Display};
+use rusqlite::{types::Type, Connection, OpenFlags, Result};
+use std::{collections::HashMap, fmt::Display, path::PathBuf, sync::Arc};
 
-const API_BASE_URL: &str = "https://openexchangerates.org/api/latest.json";
 const EXPIRE_AFTER: i64 = 60 * 60 * 24 * 7; // 1 week
+const DB_PATH: &str = "conversion_cache.db";
+/// Version of the database schema written by this code, older files are migrated on load.
+const SCHEMA_VERSION: u32 = 2;
+
+/// Settings of a ConversionCache.
+#[derive(Clone)]
+pub struct CacheConfig {
+    /// Time after which the cached rates are requested again
+    pub expire_after: TimeDelta,
+    /// SQLite database the rates are persisted in (":memory:" to not persist them)
+    pub db_path: PathBuf,
+    /// Source the rates are requested from
+    pub provider: Arc<dyn ExchangeRateProvider>,
+    /// Called before the rates are requested, e.g. to show progress
+    pub on_fetch_start: Option<Arc<dyn Fn() + Send + Sync>>,
+    /// Called after the rates were requested, with whether the request succeeded
+    pub on_fetch_end: Option<Arc<dyn Fn(bool) + Send + Sync>>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            expire_after: TimeDelta::new(EXPIRE_AFTER, 0).unwrap(),
+            db_path: PathBuf::from(DB_PATH),
+            provider: Arc::new(OpenExchangeRatesProvider::default()),
+            on_fetch_start: None,
+            on_fetch_end: None,
+        }
+    }
+}
+
+impl std::fmt::Debug for CacheConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CacheConfig")
+            .field("expire_after", &self.expire_after)
+            .field("db_path", &self.db_path)
+            .finish_non_exhaustive()
+    }
+}
 
 pub struct ConversionCache {
     /// Map from starting currency to base currency (USD) and timestamp of last update
     cache: HashMap<CurrencyUnit, f64>,
-    /// Time after which a cache line expires
-    expire_after: TimeDelta,
+    config: CacheConfig,
     last_time: Option<DateTime<Utc>>,
 }
 
 impl Default for ConversionCache {
     fn default() -> Self {
-        ConversionCache {
-            cache: HashMap::new(),
-            expire_after: TimeDelta::new(EXPIRE_AFTER, 0).unwrap(),
-            last_time: None,
-        }
+        ConversionCache::empty(CacheConfig::default())
     }
 }
 
 impl ConversionCache {
-    /// Create a new ConversionCache with a given expiration time.
+    /// Create a new ConversionCache with the default configuration.
     pub fn new() -> Self {
-        match Self::load_from_db() {
+        Self::with_config(CacheConfig::default())
+    }
+
+    /// Create a new ConversionCache, loading previously saved rates from the configured database.
+    pub fn with_config(config: CacheConfig) -> Self {
+        match Self::load_from_db(&config) {
             Ok(cache) => cache,
-            Err(_) => Self::default(),
+            Err(_) => Self::empty(config),
         }
     }
 
+    /// Create a new ConversionCache requesting its rates from the given provider.
+    pub fn with_provider(provider: Box<dyn ExchangeRateProvider>) -> Self {
+        Self::with_config(CacheConfig {
+            provider: Arc::from(provider),
+            ..Default::default()
+        })
+    }
+
+    fn empty(config: CacheConfig) -> Self {
+        ConversionCache {
+            cache: HashMap::new(),
+            config,
+            last_time: None,
+        }
+    }
+
+    /// Drop all cached rates, the next lookup requests them again.
+    pub fn invalidate(&mut self) {
+        self.cache.clear();
+        self.last_time = None;
+    }
+
+    /// Whether the rates have never been fetched or are older than the configured expiry.
+    pub fn is_stale(&self) -> bool {
+        match self.age() {
+            Some(age) => age > self.config.expire_after,
+            None => true,
+        }
+    }
+
+    /// Time the rates were last updated, if they were ever fetched.
+    pub fn last_update(&self) -> Option<DateTime<Utc>> {
+        self.last_time
+    }
+
+    /// Time since the rates were last updated.
+    pub fn age(&self) -> Option<TimeDelta> {
+        self.last_time.map(|last_time| Utc::now() - last_time)
+    }
+
     /// Get the conversion rate from USD to a given currency.
     /// I.e. how many fromUnit is one USD worth?
     pub fn get_base_rate(&mut self, from: CurrencyUnit) -> Result<f64, APIError> {
-        if self.last_time.is_none() || self.last_time.unwrap() + self.expire_after < Utc::now() {
+        if self.is_stale() {
             self.request_and_update(from)
         } else {
             let entry = self.cache.get(&from);
@@ -49,75 +127,51 @@ impl ConversionCache {
         }
     }
 
-    /// Request the conversion rate from the API and update the cache accordingly.
+    /// Request the conversion rate from the provider and update the cache accordingly.
     fn request_and_update(&mut self, from: CurrencyUnit) -> Result<f64, APIError> {
-        let response = self.request()?;
-        self.update(response)?;
-        self.cache.get(&from).cloned().ok_or(APIError {
-            message: "Rate not found".to_string(),
-        })
+        self.fetch_started();
+        let rates = self.config.provider.fetch_rates();
+        self.fetch_ended(rates.is_ok());
+        self.update(rates?, Utc::now());
+        self.cache
+            .get(&from)
+            .cloned()
+            .ok_or(APIError::new("Rate not found"))
     }
 
-    /// Request conversion rates from USD to all other currencies.
-    fn request(&self) -> Result<Value, APIError> {
-        let app_id = std::env::var("OPENEXCHANGERATES_APP_ID").map_err(|_| APIError {
-            message: "API key not found".to_string(),
-        })?;
-        let body = reqwest::blocking::get(format!("{}?app_id={}", API_BASE_URL, app_id))?
-            .json::<serde_json::Value>()?;
-        Ok(body)
-    }
-
-    /// Update the cache with the given response.
-    /// The response should be the JSON object returned by the specified API.
-    fn update(&mut self, response: Value) -> Result<(), APIError> {
-        let timestamp: DateTime<Utc> = response["timestamp"]
-            .as_i64()
-            .map(|n| DateTime::from_timestamp(n, 0))
-            .unwrap_or_else(|| Some(Utc::now()))
-            .unwrap(); // Never panics because Utc::now() always works
-
-        let rates = response["rates"].as_object().ok_or(APIError {
-            message: "Rates not found".to_string(),
-        })?;
+    fn fetch_started(&self) {
+        if let Some(on_fetch_start) = &self.config.on_fetch_start {
+            on_fetch_start();
+        }
+    }
 
-        for (currency, rate) in rates {
-            let rate = rate.as_f64().ok_or(APIError {
-                message: "Invalid rate format".to_string(),
-            })?;
-            match currency.parse() {
-                Ok(currency) => {
-                    self.cache.insert(currency, rate);
-                    Some(())
-                }
-                Err(_) => continue,
-            };
+    fn fetch_ended(&self, success: bool) {
+        if let Some(on_fetch_end) = &self.config.on_fetch_end {
+            on_fetch_end(success);
         }
+    }
+
+    /// Update the cache with the given rates, fetched at the given time.
+    fn update(&mut self, rates: HashMap<CurrencyUnit, f64>, timestamp: DateTime<Utc>) {
+        self.cache.extend(rates);
         self.last_time = Some(timestamp);
         let _ = self.save_to_db();
-        Ok(())
     }
 
     /// Save the cache to the database.
     fn save_to_db(&self) -> Result<()> {
-        let conn = Connection::open("conversion_cache.db")?;
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS conversion_cache (
-                currency TEXT PRIMARY KEY,
-                rate REAL,
-                last_update TEXT
-            )",
-            [],
-        )?;
+        let conn = Connection::open(&self.config.db_path)?;
+        upgrade_schema(&conn)?;
 
         for (currency, rate) in self.cache.iter() {
             conn.execute(
-                "INSERT OR REPLACE INTO conversion_cache (currency, rate, last_update)
-                VALUES (?, ?, ?)",
+                "INSERT OR REPLACE INTO conversion_cache (currency, rate, last_update, base)
+                VALUES (?, ?, ?, ?)",
                 [
                     currency.to_string(),
                     rate.to_string(),
-                    self.last_time.unwrap().to_string(),
+                    self.last_time.unwrap().to_rfc3339(),
+                    CurrencyUnit::USD.to_string(),
                 ],
             )?;
         }
@@ -125,39 +179,177 @@ impl ConversionCache {
     }
 
     /// Load the cache from the database.
-    fn load_from_db() -> Result<Self, Box<dyn std::error::Error>> {
-        let conn = Connection::open("conversion_cache.db")?;
-        let mut stmt = conn.prepare("SELECT * FROM conversion_cache")?;
+    fn load_from_db(config: &CacheConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        // without the create flag, so creating a cache does not create the database file
+        let conn = Connection::open_with_flags(&config.db_path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+        upgrade_schema(&conn)?;
+        let mut stmt = conn.prepare("SELECT currency, rate, last_update FROM conversion_cache")?;
         let rows = stmt.query_map([], |row| {
             let currency: String = row.get(0)?;
             let rate: f64 = row.get(1)?;
             let last_update_str: String = row.get(2)?;
 
-            let currency_unit = currency.parse().expect("Invalid currency unit");
-            let last_update = last_update_str.parse().expect("Invalid timestamp");
+            let currency_unit: CurrencyUnit = currency.parse().map_err(|e: String| {
+                rusqlite::Error::FromSqlConversionFailure(0, Type::Text, e.into())
+            })?;
+            let last_update = DateTime::parse_from_rfc3339(&last_update_str)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| {
+                    rusqlite::Error::FromSqlConversionFailure(2, Type::Text, Box::new(e))
+                })?;
 
             Ok((currency_unit, rate, last_update))
         })?;
 
         let mut cache: HashMap<CurrencyUnit, f64> = HashMap::new();
-        let mut last_update = Utc::now(); // Initialize last_update with a default value
+        // an empty database has never been updated
+        let mut last_update = None;
         for row_result in rows {
             let (currency, rate, last_update_from_row) = row_result?;
             cache.insert(currency, rate);
-            last_update = last_update_from_row;
+            last_update = Some(last_update_from_row);
         }
         Ok(ConversionCache {
             cache,
-            expire_after: TimeDelta::new(EXPIRE_AFTER, 0).unwrap(),
-            last_time: Some(last_update),
+            config: config.clone(),
+            last_time: last_update,
         })
     }
 }
 
+/// Schema version of the database, 0 for an empty one.
+/// Files written before the version was recorded have version 1.
+fn schema_version(conn: &Connection) -> Result<u32> {
+    let table_exists = |name: &str| -> Result<bool> {
+        conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+            [name],
+            |row| row.get::<_, u32>(0),
+        )
+        .map(|count| count > 0)
+    };
+    if table_exists("db_version")? {
+        conn.query_row("SELECT version FROM db_version", [], |row| row.get(0))
+    } else if table_exists("conversion_cache")? {
+        Ok(1)
+    } else {
+        Ok(0)
+    }
+}
+
+/// Bring the database to the current schema version, newer versions are left as they are.
+fn upgrade_schema(conn: &Connection) -> Result<()> {
+    let version = schema_version(conn)?;
+    if version < SCHEMA_VERSION {
+        migrate(conn, version, SCHEMA_VERSION)?;
+    }
+    Ok(())
+}
+
+/// Apply the schema changes between two versions and record the new version.
+fn migrate(conn: &Connection, from_version: u32, to_version: u32) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    for version in from_version..to_version {
+        match version {
+            0 => tx.execute_batch(
+                "CREATE TABLE conversion_cache (
+                    currency TEXT PRIMARY KEY,
+                    rate REAL,
+                    last_update TEXT
+                )",
+            )?,
+            // the currency the rate converts from
+            1 => tx.execute_batch(
+                "ALTER TABLE conversion_cache ADD COLUMN base TEXT NOT NULL DEFAULT 'USD'",
+            )?,
+            _ => unreachable!("no migration from schema version {}", version),
+        }
+    }
+    tx.execute_batch(
+        "CREATE TABLE IF NOT EXISTS db_version (version INTEGER NOT NULL);
+        DELETE FROM db_version;",
+    )?;
+    tx.execute("INSERT INTO db_version (version) VALUES (?)", [to_version])?;
+    tx.commit()
+}
+
+#[cfg(feature = "async")]
+impl ConversionCache {
+    /// Async variant of `get_base_rate`, which does not block the thread during requests.
+    pub async fn get_base_rate_async(&mut self, from: CurrencyUnit) -> Result<f64, APIError> {
+        if self.is_stale() {
+            self.request_and_update_async(from).await
+        } else {
+            match self.cache.get(&from) {
+                Some(rate) => Ok(*rate),
+                None => self.request_and_update_async(from).await,
+            }
+        }
+    }
+
+    /// Request the conversion rate from the provider and update the cache accordingly.
+    async fn request_and_update_async(&mut self, from: CurrencyUnit) -> Result<f64, APIError> {
+        self.fetch_started();
+        let rates = self.request_async().await;
+        self.fetch_ended(rates.is_ok());
+        self.update(rates?, Utc::now());
+        self.cache
+            .get(&from)
+            .cloned()
+            .ok_or(APIError::new("Rate not found"))
+    }
+
+    /// Request conversion rates from USD to all other currencies without blocking.
+    /// Providers are synchronous, so the request runs on tokio's blocking thread pool.
+    async fn request_async(&self) -> Result<HashMap<CurrencyUnit, f64>, APIError> {
+        let provider = Arc::clone(&self.config.provider);
+        tokio::task::spawn_blocking(move || provider.fetch_rates())
+            .await
+            .map_err(|e| APIError::new(e.to_string()))?
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct APIError {
     /// Error type for API requests.
     message: String,
+    /// Underlying error of the request, if any.
+    source: Option<Arc<dyn std::error::Error + Send + Sync + 'static>>,
+    /// HTTP status of the response, if the API answered with an error.
+    pub status: Option<reqwest::StatusCode>,
+}
+
+impl APIError {
+    /// Create an error with the given message, e.g. in a custom ExchangeRateProvider.
+    pub fn new(message: impl Into<String>) -> Self {
+        APIError {
+            message: message.into(),
+            source: None,
+            status: None,
+        }
+    }
+
+    /// Create an error for a response with an unsuccessful HTTP status.
+    pub fn from_status(status: reqwest::StatusCode) -> Self {
+        APIError {
+            message: status.to_string(),
+            source: None,
+            status: Some(status),
+        }
+    }
+
+    /// Whether the API rejected the credentials, i.e. the API key is missing or invalid.
+    pub fn is_auth_error(&self) -> bool {
+        matches!(
+            self.status,
+            Some(reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN)
+        )
+    }
+
+    /// Whether too many requests were made to the API.
+    pub fn is_rate_limited(&self) -> bool {
+        self.status == Some(reqwest::StatusCode::TOO_MANY_REQUESTS)
+    }
 }
 
 impl Display for APIError {
@@ -166,108 +358,344 @@ impl Display for APIError {
     }
 }
 
+impl std::error::Error for APIError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
 impl From<reqwest::Error> for APIError {
     fn from(e: reqwest::Error) -> Self {
         APIError {
             message: e.to_string(),
+            status: e.status(),
+            source: Some(Arc::new(e)),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::time::Instant;
-
     use super::*;
-    use serde_json::json;
+    use crate::core::providers::MockExchangeRateProvider;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    fn in_memory_cache(expire_after: TimeDelta) -> ConversionCache {
+        ConversionCache::with_config(CacheConfig {
+            expire_after,
+            db_path: PathBuf::from(":memory:"),
+            provider: Arc::new(MockExchangeRateProvider::default()),
+            ..Default::default()
+        })
+    }
 
     #[test]
     fn test_get_entry_multiple_times() {
-        let mut cache = ConversionCache::new();
-        let start = Instant::now();
-        let rate = cache.get_base_rate(CurrencyUnit::EUR);
-        let duration_fst = start.elapsed();
-        assert!(rate.is_ok());
-
-        let repeat_count = 10;
-        let mut total_duration = std::time::Duration::new(0, 0);
-        for _ in 0..repeat_count {
-            let start = Instant::now();
-            let rate_new = cache.get_base_rate(CurrencyUnit::EUR);
-            total_duration += start.elapsed();
-            assert!(rate_new.is_ok());
-            assert!(rate.clone().unwrap() == rate_new.unwrap());
+        let mut cache = in_memory_cache(TimeDelta::try_days(1).unwrap());
+        let rate = cache.get_base_rate(CurrencyUnit::EUR).unwrap();
+        let last_time = cache.last_time;
+
+        for _ in 0..10 {
+            assert_eq!(cache.get_base_rate(CurrencyUnit::EUR).unwrap(), rate);
         }
+        // subsequent calls are served from the cache without a new request
+        assert_eq!(cache.last_time, last_time);
+    }
 
-        let average_duration = total_duration / repeat_count;
+    #[test]
+    fn test_save_to_db_and_load_from_db() {
+        let config = temp_db_config("save_and_load");
+        let mut cache = ConversionCache::with_config(config.clone());
+        cache.update(
+            HashMap::from([(CurrencyUnit::EUR, 1.0), (CurrencyUnit::USD, 1.2)]),
+            Utc::now(),
+        );
+        assert!(cache.save_to_db().is_ok());
 
-        // implicitly check that subsequent calls do not require a new API request,
-        // therefore should be faster than the first call.
-        assert!(duration_fst > average_duration);
+        let loaded_cache = ConversionCache::load_from_db(&config);
+        assert!(loaded_cache.is_ok());
+        assert_eq!(cache.cache, loaded_cache.unwrap().cache);
+        let _ = std::fs::remove_file(&config.db_path);
     }
 
     #[test]
-    fn test_update_with_valid_response() {
-        let mut cache = ConversionCache::new();
-        let response = json!({
-            "timestamp": Utc::now().timestamp(),
-            "rates": {
-                "EUR": 1.0,
-                "USD": 1.2
-            }
-        });
-        assert!(cache.update(response).is_ok());
+    fn test_with_provider() {
+        let provider = MockExchangeRateProvider {
+            rates: HashMap::from([(CurrencyUnit::JPY, 150.0)]),
+        };
+        let cache = ConversionCache::with_provider(Box::new(provider));
+        let rates = cache.config.provider.fetch_rates().unwrap();
+        assert_eq!(rates, HashMap::from([(CurrencyUnit::JPY, 150.0)]));
     }
 
     #[test]
-    fn test_update_with_invalid_rate() {
-        let mut cache = ConversionCache::new();
-        let response = json!({
-            "timestamp": Utc::now().timestamp(),
-            "rates": {
-                "EUR": "invalid",
-                "USD": 1.2
-            }
-        });
-        assert!(cache.update(response).is_err());
+    fn test_rates_are_fetched_from_provider() {
+        let mut cache = in_memory_cache(TimeDelta::try_days(1).unwrap());
+        assert_eq!(cache.get_base_rate(CurrencyUnit::EUR).unwrap(), 0.9);
+        assert_eq!(cache.get_base_rate(CurrencyUnit::GBP).unwrap(), 0.8);
+        assert!(!cache.is_stale());
+        assert!(cache.get_base_rate(CurrencyUnit::JPY).is_err());
     }
 
     #[test]
-    fn test_update_with_invalid_timestamp() {
-        let mut cache = ConversionCache::new();
-        let response = json!({
-            "timestamp": "invalid",
-            "rates": {
-                "EUR": 1.0,
-                "USD": 1.2
-            }
-        });
-        assert!(cache.update(response).is_ok());
+    fn test_zero_expiry_always_refreshes() {
+        let mut cache = in_memory_cache(TimeDelta::zero());
+        cache.update(
+            HashMap::from([(CurrencyUnit::EUR, 123.0)]),
+            Utc::now() - TimeDelta::try_seconds(1).unwrap(),
+        );
+
+        // the cached rate must not be served, the provider is asked again instead
+        assert_eq!(cache.get_base_rate(CurrencyUnit::EUR).unwrap(), 0.9);
     }
 
     #[test]
-    fn test_save_to_db_and_load_from_db() {
-        let mut cache = ConversionCache::new();
-        let response = json!({
-            "timestamp": Utc::now().timestamp(),
-            "rates": {
-                "EUR": 1.0,
-                "USD": 1.2
+    fn test_long_expiry_uses_cached_rate() {
+        let mut cache = in_memory_cache(TimeDelta::try_days(365 * 100).unwrap());
+        cache.update(HashMap::from([(CurrencyUnit::EUR, 123.0)]), Utc::now());
+
+        for _ in 0..3 {
+            assert_eq!(cache.get_base_rate(CurrencyUnit::EUR).unwrap(), 123.0);
+        }
+    }
+
+    #[test]
+    fn test_invalidate() {
+        let mut cache = in_memory_cache(TimeDelta::try_days(365 * 100).unwrap());
+        assert!(cache.is_stale());
+        assert!(cache.age().is_none());
+
+        cache.update(HashMap::from([(CurrencyUnit::EUR, 123.0)]), Utc::now());
+        assert!(!cache.is_stale());
+        assert!(cache.age().is_some());
+
+        cache.invalidate();
+        assert!(cache.is_stale());
+        assert!(cache.age().is_none());
+        assert!(cache.cache.is_empty());
+    }
+
+    #[test]
+    fn test_is_stale_after_expiry() {
+        let mut cache = in_memory_cache(TimeDelta::try_hours(1).unwrap());
+        cache.update(
+            HashMap::from([(CurrencyUnit::EUR, 123.0)]),
+            Utc::now() - TimeDelta::try_hours(2).unwrap(),
+        );
+        assert!(cache.is_stale());
+        assert!(cache.age().unwrap() >= TimeDelta::try_hours(2).unwrap());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_get_base_rate_async_uses_cached_rate() {
+        let mut cache = in_memory_cache(TimeDelta::try_days(365 * 100).unwrap());
+        cache.update(HashMap::from([(CurrencyUnit::EUR, 123.0)]), Utc::now());
+        assert_eq!(
+            cache.get_base_rate_async(CurrencyUnit::EUR).await.unwrap(),
+            123.0
+        );
+    }
+
+    /// Provider whose requests always fail.
+    struct FailingProvider;
+
+    impl ExchangeRateProvider for FailingProvider {
+        fn fetch_rates(&self) -> Result<HashMap<CurrencyUnit, f64>, APIError> {
+            Err(APIError::new("offline"))
+        }
+    }
+
+    #[test]
+    fn test_fetch_callbacks() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let ended = Arc::new(Mutex::new(Vec::new()));
+        let config = {
+            let started = Arc::clone(&started);
+            let ended = Arc::clone(&ended);
+            CacheConfig {
+                db_path: PathBuf::from(":memory:"),
+                provider: Arc::new(MockExchangeRateProvider::default()),
+                on_fetch_start: Some(Arc::new(move || {
+                    started.fetch_add(1, Ordering::SeqCst);
+                })),
+                on_fetch_end: Some(Arc::new(move |success| {
+                    ended.lock().unwrap().push(success);
+                })),
+                ..Default::default()
             }
+        };
+
+        let mut cache = ConversionCache::with_config(config.clone());
+        cache.get_base_rate(CurrencyUnit::EUR).unwrap();
+        cache.get_base_rate(CurrencyUnit::GBP).unwrap();
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+        assert_eq!(*ended.lock().unwrap(), [true]);
+
+        let mut cache = ConversionCache::with_config(CacheConfig {
+            provider: Arc::new(FailingProvider),
+            ..config
         });
-        assert!(cache.update(response).is_ok());
-        assert!(cache.save_to_db().is_ok());
+        assert!(cache.get_base_rate(CurrencyUnit::EUR).is_err());
+        assert_eq!(started.load(Ordering::SeqCst), 2);
+        assert_eq!(*ended.lock().unwrap(), [true, false]);
+    }
 
-        let loaded_cache = ConversionCache::load_from_db();
-        assert!(loaded_cache.is_ok());
-        assert_eq!(cache.cache, loaded_cache.unwrap().cache);
+    /// Config for a database file that is only used by a single test.
+    fn temp_db_config(name: &str) -> CacheConfig {
+        let db_path = std::env::temp_dir().join(format!("unit_conv_{}.db", name));
+        let _ = std::fs::remove_file(&db_path);
+        CacheConfig {
+            db_path,
+            provider: Arc::new(MockExchangeRateProvider::default()),
+            ..Default::default()
+        }
+    }
+
+    fn insert_row(config: &CacheConfig, last_update: &str) {
+        let conn = Connection::open(&config.db_path).unwrap();
+        conn.execute(
+            "CREATE TABLE conversion_cache (currency TEXT PRIMARY KEY, rate REAL, last_update TEXT)",
+            [],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO conversion_cache VALUES ('EUR', 0.9, ?)",
+            [last_update],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_load_from_db_rfc3339_timestamp() {
+        let config = temp_db_config("rfc3339");
+        insert_row(&config, "2024-03-01T12:30:00+00:00");
+
+        let cache = ConversionCache::load_from_db(&config).unwrap();
+        assert_eq!(cache.cache.get(&CurrencyUnit::EUR), Some(&0.9));
+        assert_eq!(
+            cache.last_time,
+            Some(DateTime::from_timestamp(1709296200, 0).unwrap())
+        );
+        let _ = std::fs::remove_file(&config.db_path);
+    }
+
+    #[test]
+    fn test_load_from_db_invalid_timestamp() {
+        let config = temp_db_config("invalid_timestamp");
+        insert_row(&config, "yesterday");
+
+        assert!(ConversionCache::load_from_db(&config).is_err());
+        let _ = std::fs::remove_file(&config.db_path);
+    }
+
+    #[test]
+    fn test_load_from_db_invalid_currency() {
+        let config = temp_db_config("invalid_currency");
+        insert_row(&config, "2024-03-01T12:00:00+00:00");
+        let conn = Connection::open(&config.db_path).unwrap();
+        conn.execute(
+            "INSERT INTO conversion_cache VALUES ('XYZ', 1.0, '2024-03-01T12:00:00+00:00')",
+            [],
+        )
+        .unwrap();
+
+        assert!(ConversionCache::load_from_db(&config).is_err());
+        // the cache starts empty instead of crashing
+        assert_eq!(
+            ConversionCache::with_config(config.clone()).last_update(),
+            None
+        );
+        let _ = std::fs::remove_file(&config.db_path);
+    }
+
+    fn columns(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT name FROM pragma_table_info('conversion_cache')")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_migrate() {
+        let conn = Connection::open_in_memory().unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 0);
+
+        migrate(&conn, 0, 1).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 1);
+        assert_eq!(columns(&conn), ["currency", "rate", "last_update"]);
+        conn.execute(
+            "INSERT INTO conversion_cache VALUES ('EUR', 0.9, '2024-03-01T12:30:00+00:00')",
+            [],
+        )
+        .unwrap();
+
+        migrate(&conn, 1, 2).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), 2);
+        assert_eq!(columns(&conn), ["currency", "rate", "last_update", "base"]);
+        let base: String = conn
+            .query_row("SELECT base FROM conversion_cache", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(base, "USD");
+    }
+
+    #[test]
+    fn test_load_from_db_migrates_old_schema() {
+        // written before the schema version was recorded
+        let config = temp_db_config("old_schema");
+        insert_row(&config, "2024-03-01T12:30:00+00:00");
+
+        let mut cache = ConversionCache::load_from_db(&config).unwrap();
+        assert_eq!(cache.cache.get(&CurrencyUnit::EUR), Some(&0.9));
+        let conn = Connection::open(&config.db_path).unwrap();
+        assert_eq!(schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        cache.update(HashMap::from([(CurrencyUnit::GBP, 0.8)]), Utc::now());
+        let loaded = ConversionCache::load_from_db(&config).unwrap();
+        assert_eq!(loaded.cache.get(&CurrencyUnit::GBP), Some(&0.8));
+        let _ = std::fs::remove_file(&config.db_path);
+    }
+
+    #[test]
+    fn test_default_config() {
+        let config = CacheConfig::default();
+        assert_eq!(config.expire_after, TimeDelta::try_weeks(1).unwrap());
+        assert_eq!(config.db_path, PathBuf::from("conversion_cache.db"));
     }
 
     #[test]
     fn test_api_error_display() {
-        let error = APIError {
-            message: "Test error".to_string(),
-        };
+        let error = APIError::new("Test error");
         assert_eq!(format!("{}", error), "API error: Test error");
     }
+
+    #[test]
+    fn test_api_error_is_std_error() {
+        let error: Box<dyn std::error::Error> = Box::new(APIError::new("Test error"));
+        assert_eq!(error.to_string(), "API error: Test error");
+        assert!(error.source().is_none());
+    }
+
+    #[test]
+    fn test_api_error_status() {
+        let error = APIError::from_status(reqwest::StatusCode::UNAUTHORIZED);
+        assert_eq!(error.to_string(), "API error: 401 Unauthorized");
+        assert!(error.is_auth_error());
+        assert!(!error.is_rate_limited());
+
+        assert!(APIError::from_status(reqwest::StatusCode::FORBIDDEN).is_auth_error());
+        let error = APIError::from_status(reqwest::StatusCode::TOO_MANY_REQUESTS);
+        assert!(error.is_rate_limited());
+        assert!(!error.is_auth_error());
+
+        let error = APIError::new("Test error");
+        assert_eq!(error.status, None);
+        assert!(!error.is_auth_error());
+    }
 }
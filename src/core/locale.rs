@@ -0,0 +1,97 @@
+use super::units::Unit;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// Unit names of the supported locales, mapped to names understood by the default parser.
+const LOCALE_FILES: [(&str, &str); 4] = [
+    ("en", include_str!("../locales/en.toml")),
+    ("fr", include_str!("../locales/fr.toml")),
+    ("de", include_str!("../locales/de.toml")),
+    ("es", include_str!("../locales/es.toml")),
+];
+
+static LOCALES: Lazy<HashMap<&'static str, LocaleUnitMap>> = Lazy::new(|| {
+    LOCALE_FILES
+        .iter()
+        .map(|(locale, content)| (*locale, LocaleUnitMap::from_toml(content)))
+        .collect()
+});
+
+/// Unit names used in a locale, e.g. "kilomètre" in French or "Kilometer" in German.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocaleUnitMap {
+    units: HashMap<String, Unit>,
+}
+
+impl LocaleUnitMap {
+    /// Map of a supported locale, the region is ignored, e.g. "fr", "fr-CA" or "de_DE".
+    pub fn get(locale: &str) -> Option<&'static LocaleUnitMap> {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        LOCALES.get(language.to_ascii_lowercase().as_str())
+    }
+
+    /// Languages with a map, e.g. "fr".
+    pub fn locales() -> impl Iterator<Item = &'static str> {
+        LOCALE_FILES.iter().map(|(locale, _)| *locale)
+    }
+
+    /// Unit with the given locale-specific name, ignoring case unless `exact` is set.
+    pub fn find(&self, s: &str, exact: bool) -> Option<Unit> {
+        let s = s.trim();
+        match self.units.get(s) {
+            Some(unit) => Some(*unit),
+            None if exact => None,
+            None => {
+                let s = s.to_lowercase();
+                self.units
+                    .iter()
+                    .find(|(name, _)| name.to_lowercase() == s)
+                    .map(|(_, unit)| *unit)
+            }
+        }
+    }
+
+    /// Panics on invalid content, the locale files are embedded and checked by the tests.
+    fn from_toml(content: &str) -> LocaleUnitMap {
+        let names: HashMap<String, String> = toml::from_str(content).unwrap();
+        let units = names
+            .into_iter()
+            .map(|(name, unit)| {
+                let unit = unit
+                    .parse()
+                    .unwrap_or_else(|e| panic!("Invalid unit for '{}': {}", name, e));
+                (name, unit)
+            })
+            .collect();
+        LocaleUnitMap { units }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::units::{LengthUnit, MassUnit};
+
+    #[test]
+    fn test_locale_files() {
+        for locale in LocaleUnitMap::locales() {
+            assert!(!LocaleUnitMap::get(locale).unwrap().units.is_empty());
+        }
+        assert!(LocaleUnitMap::get("it").is_none());
+    }
+
+    #[test]
+    fn test_find() {
+        let fr = LocaleUnitMap::get("fr-CA").unwrap();
+        assert_eq!(
+            fr.find("kilomètre", true),
+            Some(Unit::Length(LengthUnit::Kilometer))
+        );
+        assert_eq!(
+            fr.find("Tonnes", false),
+            Some(Unit::Mass(MassUnit::MetricTon))
+        );
+        assert_eq!(fr.find("Tonnes", true), None);
+        assert_eq!(fr.find("km", false), None);
+    }
+}
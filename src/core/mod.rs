@@ -1,3 +1,7 @@
 pub mod commands;
+pub mod context;
 pub mod currency;
+pub mod locale;
+pub mod providers;
+pub mod result_cache;
 pub mod units;
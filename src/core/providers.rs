@@ -0,0 +1,232 @@
+use super::currency::APIError;
+use super::units::CurrencyUnit;
+use reqwest;
+use serde_json::Value;
+use std::collections::HashMap;
+
+const OPEN_EXCHANGE_RATES_URL: &str = "https://openexchangerates.org/api/latest.json";
+const FRANKFURTER_URL: &str = "https://api.frankfurter.app/latest?from=USD";
+
+/// Source of the exchange rates used by a ConversionCache.
+pub trait ExchangeRateProvider: Send + Sync {
+    /// Fetch the conversion rates from USD to all other currencies.
+    /// I.e. how many units of each currency is one USD worth?
+    fn fetch_rates(&self) -> Result<HashMap<CurrencyUnit, f64>, APIError>;
+}
+
+/// Environment variable with the API key of openexchangerates.org.
+pub const APP_ID_VAR: &str = "OPENEXCHANGERATES_APP_ID";
+/// Service and user the API key is stored under in the system keyring.
+pub const KEYRING_SERVICE: &str = "convert-rs";
+pub const KEYRING_USER: &str = "openexchangerates";
+
+/// API key of openexchangerates.org, from the environment variable or else the system keyring.
+/// The keyring is only checked with the `keyring` feature.
+pub fn get_api_key() -> Result<String, APIError> {
+    api_key_from(std::env::var(APP_ID_VAR).ok(), keyring_api_key)
+}
+
+/// The key from the environment, only looking into the keyring if there is none.
+fn api_key_from(
+    env: Option<String>,
+    keyring: impl FnOnce() -> Option<String>,
+) -> Result<String, APIError> {
+    env.filter(|key| !key.is_empty())
+        .or_else(keyring)
+        .ok_or(APIError::new("API key not found"))
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_entry() -> Result<keyring::Entry, APIError> {
+    keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER)
+        .map_err(|e| APIError::new(format!("Keyring error: {}", e)))
+}
+
+#[cfg(feature = "keyring")]
+fn keyring_api_key() -> Option<String> {
+    keyring_entry().ok()?.get_password().ok()
+}
+
+#[cfg(not(feature = "keyring"))]
+fn keyring_api_key() -> Option<String> {
+    None
+}
+
+/// Store the API key in the system keyring, where `get_api_key` finds it.
+#[cfg(feature = "keyring")]
+pub fn store_api_key(key: &str) -> Result<(), APIError> {
+    keyring_entry()?
+        .set_password(key)
+        .map_err(|e| APIError::new(format!("Keyring error: {}", e)))
+}
+
+/// Store the API key in the system keyring, which needs the `keyring` feature.
+#[cfg(not(feature = "keyring"))]
+pub fn store_api_key(_key: &str) -> Result<(), APIError> {
+    Err(APIError::new(
+        "Keyring support is not enabled, rebuild with --features keyring",
+    ))
+}
+
+/// Rates from openexchangerates.org, the API key is read from `OPENEXCHANGERATES_APP_ID`
+/// or the system keyring, see `get_api_key`.
+#[derive(Debug, Default, Clone)]
+pub struct OpenExchangeRatesProvider {
+    /// API key used when neither the environment variable nor the keyring has one
+    app_id: Option<String>,
+}
+
+impl OpenExchangeRatesProvider {
+    /// Provider falling back to the given API key, the environment variable and keyring still take precedence.
+    pub fn with_app_id(app_id: impl Into<String>) -> Self {
+        OpenExchangeRatesProvider {
+            app_id: Some(app_id.into()),
+        }
+    }
+}
+
+impl ExchangeRateProvider for OpenExchangeRatesProvider {
+    fn fetch_rates(&self) -> Result<HashMap<CurrencyUnit, f64>, APIError> {
+        let app_id = get_api_key().or_else(|e| self.app_id.clone().ok_or(e))?;
+        let url = format!("{}?app_id={}", OPEN_EXCHANGE_RATES_URL, app_id);
+        parse_rates(&get_json(&url)?)
+    }
+}
+
+/// Rates from frankfurter.app, which needs no API key.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FrankfurterProvider;
+
+impl ExchangeRateProvider for FrankfurterProvider {
+    fn fetch_rates(&self) -> Result<HashMap<CurrencyUnit, f64>, APIError> {
+        let mut rates = parse_rates(&get_json(FRANKFURTER_URL)?)?;
+        // the base currency is not part of the response
+        rates.insert(CurrencyUnit::USD, 1.0);
+        Ok(rates)
+    }
+}
+
+/// Fixed rates without any network access, for tests and offline use.
+#[derive(Debug, Clone)]
+pub struct MockExchangeRateProvider {
+    /// Rates returned by every fetch, relative to USD
+    pub rates: HashMap<CurrencyUnit, f64>,
+}
+
+impl Default for MockExchangeRateProvider {
+    fn default() -> Self {
+        MockExchangeRateProvider {
+            rates: HashMap::from([
+                (CurrencyUnit::USD, 1.0),
+                (CurrencyUnit::EUR, 0.9),
+                (CurrencyUnit::GBP, 0.8),
+            ]),
+        }
+    }
+}
+
+impl ExchangeRateProvider for MockExchangeRateProvider {
+    fn fetch_rates(&self) -> Result<HashMap<CurrencyUnit, f64>, APIError> {
+        Ok(self.rates.clone())
+    }
+}
+
+/// Request a JSON document, an unsuccessful HTTP status is returned as an error.
+fn get_json(url: &str) -> Result<Value, APIError> {
+    let response = reqwest::blocking::get(url)?;
+    if !response.status().is_success() {
+        return Err(APIError::from_status(response.status()));
+    }
+    Ok(response.json()?)
+}
+
+/// Read the "rates" object of an API response, unknown currencies are skipped.
+fn parse_rates(response: &Value) -> Result<HashMap<CurrencyUnit, f64>, APIError> {
+    let rates = response["rates"]
+        .as_object()
+        .ok_or(APIError::new("Rates not found"))?;
+
+    let mut parsed = HashMap::new();
+    for (currency, rate) in rates {
+        let rate = rate.as_f64().ok_or(APIError::new("Invalid rate format"))?;
+        if let Ok(currency) = currency.parse() {
+            parsed.insert(currency, rate);
+        }
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_mock_provider() {
+        let provider = MockExchangeRateProvider::default();
+        let rates = provider.fetch_rates().unwrap();
+        assert_eq!(rates[&CurrencyUnit::USD], 1.0);
+        assert_eq!(rates[&CurrencyUnit::EUR], 0.9);
+        assert_eq!(rates[&CurrencyUnit::GBP], 0.8);
+
+        let provider = MockExchangeRateProvider {
+            rates: HashMap::from([(CurrencyUnit::JPY, 150.0)]),
+        };
+        assert_eq!(provider.fetch_rates().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_parse_rates() {
+        let response = json!({
+            "timestamp": 1709296200,
+            "rates": {
+                "EUR": 1.0,
+                "USD": 1.2,
+                "XYZ": 3.0
+            }
+        });
+        let rates = parse_rates(&response).unwrap();
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[&CurrencyUnit::EUR], 1.0);
+        assert_eq!(rates[&CurrencyUnit::USD], 1.2);
+    }
+
+    #[test]
+    fn test_parse_rates_invalid_rate() {
+        let response = json!({
+            "rates": {
+                "EUR": "invalid",
+                "USD": 1.2
+            }
+        });
+        assert!(parse_rates(&response).is_err());
+    }
+
+    #[test]
+    fn test_parse_rates_missing_rates() {
+        let response = json!({ "error": true });
+        assert!(parse_rates(&response).is_err());
+    }
+
+    #[test]
+    fn test_api_key_priority() {
+        let keyring = || Some("from keyring".to_string());
+        assert_eq!(
+            api_key_from(Some("from env".to_string()), || panic!("keyring checked")).unwrap(),
+            "from env"
+        );
+        assert_eq!(api_key_from(None, keyring).unwrap(), "from keyring");
+        assert_eq!(
+            api_key_from(Some(String::new()), keyring).unwrap(),
+            "from keyring"
+        );
+        assert!(api_key_from(None, || None).is_err());
+    }
+
+    #[cfg(not(feature = "keyring"))]
+    #[test]
+    fn test_store_api_key_without_keyring() {
+        assert!(store_api_key("secret").is_err());
+        assert_eq!(keyring_api_key(), None);
+    }
+}
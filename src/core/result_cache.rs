@@ -0,0 +1,214 @@
+use super::context::ExecutionContext;
+use super::currency::ConversionCache;
+use super::units::{ConversionResult, Unit};
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::collections::HashMap;
+#[cfg(not(feature = "async"))]
+use std::sync::Mutex;
+use std::sync::{Arc, Weak};
+#[cfg(feature = "async")]
+use tokio::sync::Mutex;
+
+thread_local! {
+    static RESULT_CACHE: RefCell<ConversionResultCache> = RefCell::new(ConversionResultCache::default());
+}
+
+/// Run `f` with the conversion factor cache of the current thread.
+pub(crate) fn with_result_cache<T>(f: impl FnOnce(&mut ConversionResultCache) -> T) -> T {
+    RESULT_CACHE.with(|cache| f(&mut cache.borrow_mut()))
+}
+
+/// Conversion factors between pairs of linear units (see `Convertable::is_linear`), so repeated
+/// conversions are a single multiplication instead of two factor or exchange rate lookups.
+/// Non-linear units have no single factor and bypass the cache.
+/// Factors between currencies are dropped whenever the exchange rates are refreshed or
+/// invalidated, or when converting with the cache of another ExecutionContext.
+/// Factors between other units are constants and kept for the lifetime of the thread.
+#[derive(Debug, Default)]
+pub(crate) struct ConversionResultCache {
+    factors: HashMap<(Unit, Unit), f64>,
+    /// Most recently used entry of `factors`, repeated conversions skip hashing the units
+    last: Option<((Unit, Unit), f64)>,
+    /// Factors between currencies, which depend on the exchange rates
+    rate_factors: HashMap<(Unit, Unit), f64>,
+    /// Update time of the exchange rates the cached factors are based on
+    rates_updated: Option<DateTime<Utc>>,
+    /// Cache the exchange rates were taken from
+    source: Weak<Mutex<ConversionCache>>,
+}
+
+impl ConversionResultCache {
+    /// Convert a value with the cached factor between the units, computed on first use.
+    pub(crate) fn convert(
+        &mut self,
+        value: f64,
+        from: &Unit,
+        to: &Unit,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<f64> {
+        // the factor skips the range check of the unit, e.g. for Kelvin below absolute zero
+        let in_range = from
+            .physical_range()
+            .is_none_or(|(min, max)| (min..=max).contains(&value));
+        if !(from.is_linear() && to.is_linear() && in_range) {
+            return Unit::convert_uncached(value, from, to, ctx);
+        }
+        Ok(value * self.get_or_compute(from, to, ctx)?)
+    }
+
+    /// Factor converting values of `from` to `to`, computed on first use.
+    /// Both units must be linear.
+    pub(crate) fn get_or_compute(
+        &mut self,
+        from: &Unit,
+        to: &Unit,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<f64> {
+        let (Unit::Currency(_), Unit::Currency(_)) = (from, to) else {
+            match self.last {
+                Some((units, factor)) if units == (*from, *to) => return Ok(factor),
+                _ => {}
+            }
+            let factor = match self.factors.get(&(*from, *to)) {
+                Some(&factor) => factor,
+                None => {
+                    let factor = Unit::convert_uncached(1.0, from, to, ctx)?;
+                    self.factors.insert((*from, *to), factor);
+                    factor
+                }
+            };
+            self.last = Some(((*from, *to), factor));
+            return Ok(factor);
+        };
+
+        let (stale, updated) = ctx.with_cache(|cache| (cache.is_stale(), cache.last_update()));
+        let other_source = !Weak::ptr_eq(&self.source, &Arc::downgrade(&ctx.cache));
+        if stale || updated != self.rates_updated || other_source {
+            self.rate_factors.clear();
+            self.source = Arc::downgrade(&ctx.cache);
+        }
+
+        if let Some(&factor) = self.rate_factors.get(&(*from, *to)) {
+            return Ok(factor);
+        }
+        let factor = Unit::convert_uncached(1.0, from, to, ctx)?;
+        // converting may have requested new rates
+        self.rates_updated = ctx.with_cache(|cache| cache.last_update());
+        self.rate_factors.insert((*from, *to), factor);
+        Ok(factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::currency::CacheConfig;
+    use crate::core::providers::MockExchangeRateProvider;
+    use crate::core::units::{ConversionErrorKind, CurrencyUnit, LengthUnit, TemperatureUnit};
+
+    #[test]
+    fn test_linear_factor_is_cached() {
+        let ctx = ExecutionContext::global();
+        let mut cache = ConversionResultCache::default();
+        let m = Unit::Length(LengthUnit::Meter);
+        let km = Unit::Length(LengthUnit::Kilometer);
+        assert_eq!(cache.convert(2000.0, &m, &km, ctx), Ok(2.0));
+        assert_eq!(cache.factors.get(&(m, km)), Some(&0.001));
+        assert!(cache.rate_factors.is_empty());
+
+        // constant factors outlive the exchange rates
+        cache.rates_updated = None;
+        assert_eq!(cache.convert(2000.0, &m, &km, ctx), Ok(2.0));
+        assert_eq!(cache.factors.len(), 1);
+    }
+
+    #[test]
+    fn test_non_linear_units_bypass_cache() {
+        let ctx = ExecutionContext::global();
+        let mut cache = ConversionResultCache::default();
+        let celsius = Unit::Temperature(TemperatureUnit::Celsius);
+        let fahrenheit = Unit::Temperature(TemperatureUnit::Fahrenheit);
+        let boiling = cache.convert(100.0, &celsius, &fahrenheit, ctx).unwrap();
+        assert!((boiling - 212.0).abs() < 1e-9);
+        // a factor of 2.12 from 100 °C would turn 0 °C into 0 °F
+        let freezing = cache.convert(0.0, &celsius, &fahrenheit, ctx).unwrap();
+        assert!((freezing - 32.0).abs() < 1e-9);
+        assert!(cache.factors.is_empty());
+        assert!(cache.last.is_none());
+    }
+
+    #[test]
+    fn test_kelvin_below_absolute_zero() {
+        let ctx = ExecutionContext::global();
+        let mut cache = ConversionResultCache::default();
+        let kelvin = Unit::Temperature(TemperatureUnit::Kelvin);
+        let rankine = Unit::Temperature(TemperatureUnit::Rankine);
+        assert_eq!(cache.convert(1.0, &kelvin, &rankine, ctx), Ok(1.8));
+        assert_eq!(cache.factors.get(&(kelvin, rankine)), Some(&1.8));
+
+        // the cached factor must not skip the absolute zero check
+        let result = cache.convert(-1.0, &kelvin, &rankine, ctx);
+        assert_eq!(
+            result.unwrap_err().kind(),
+            ConversionErrorKind::NegativeTemperature
+        );
+        let result = cache.convert(-1.0, &rankine, &kelvin, ctx);
+        assert_eq!(
+            result.unwrap_err().kind(),
+            ConversionErrorKind::NegativeTemperature
+        );
+    }
+
+    #[test]
+    fn test_factor_is_cached() {
+        let ctx = ExecutionContext::global();
+        let mut cache = ConversionResultCache::default();
+        let usd = Unit::Currency(CurrencyUnit::USD);
+        let gbp = Unit::Currency(CurrencyUnit::GBP);
+        let factor = cache.get_or_compute(&usd, &gbp, ctx).unwrap();
+        assert!((factor - 0.8).abs() < 1e-9);
+        assert_eq!(cache.rate_factors.get(&(usd, gbp)), Some(&factor));
+        assert_eq!(cache.get_or_compute(&usd, &gbp, ctx), Ok(factor));
+        assert_eq!(cache.rate_factors.len(), 1);
+    }
+
+    #[test]
+    fn test_factor_follows_rates() {
+        let ctx = ExecutionContext::global();
+        let mut cache = ConversionResultCache::default();
+        let usd = Unit::Currency(CurrencyUnit::USD);
+        let eur = Unit::Currency(CurrencyUnit::EUR);
+        let factor = cache.get_or_compute(&usd, &eur, ctx).unwrap();
+        assert!((factor - 0.9).abs() < 1e-9);
+        assert_eq!(
+            cache.rates_updated,
+            ctx.with_cache(|cache| cache.last_update())
+        );
+
+        // pretend the rates changed since the factor was computed
+        cache.rate_factors.insert((usd, eur), 2.0);
+        cache.rates_updated = None;
+        let factor = cache.get_or_compute(&usd, &eur, ctx).unwrap();
+        assert!((factor - 0.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_factor_follows_context() {
+        let mut cache = ConversionResultCache::default();
+        let usd = Unit::Currency(CurrencyUnit::USD);
+        let eur = Unit::Currency(CurrencyUnit::EUR);
+        let factor = cache.get_or_compute(&usd, &eur, ExecutionContext::global());
+        assert!((factor.unwrap() - 0.9).abs() < 1e-9);
+
+        let provider = MockExchangeRateProvider {
+            rates: HashMap::from([(CurrencyUnit::USD, 1.0), (CurrencyUnit::EUR, 2.0)]),
+        };
+        let ctx = ExecutionContext::new(ConversionCache::with_config(CacheConfig {
+            db_path: ":memory:".into(),
+            provider: Arc::new(provider),
+            ..Default::default()
+        }));
+        assert_eq!(cache.get_or_compute(&usd, &eur, &ctx), Ok(2.0));
+    }
+}
@@ -1,20 +1,131 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::default;
+use std::f64::consts::PI;
 use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, Div, Mul, Sub};
 use std::str::FromStr;
-use std::sync::Mutex;
-use std::{default, mem};
 
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-use super::currency::ConversionCache;
+use super::context::ExecutionContext;
+use super::locale::LocaleUnitMap;
+use super::result_cache::with_result_cache;
 use once_cell::sync::Lazy;
 
-static CACHE: Lazy<Mutex<ConversionCache>> = Lazy::new(|| Mutex::new(ConversionCache::new()));
+/// Category of a ConversionError, for handling errors programmatically.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum ConversionErrorKind {
+    /// The units cannot be converted into each other, e.g. of different categories.
+    UnsupportedConversion,
+    /// A value or unit could not be parsed.
+    ParseError,
+    /// Exchange rates could not be requested.
+    NetworkError,
+    /// Exchange rates could not be read from or written to the database.
+    DatabaseError,
+    /// A temperature below absolute zero.
+    NegativeTemperature,
+    /// A value that would have to be inverted is zero.
+    DivisionByZero,
+    /// A value outside of the allowed range.
+    OutOfRange,
+}
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct ConversionError {
+    kind: ConversionErrorKind,
     message: String,
+    /// Underlying error that caused the conversion to fail, if any.
+    #[serde(skip)]
+    source: Option<Box<dyn std::error::Error + Send + Sync + 'static>>,
+}
+
+impl ConversionError {
+    pub fn new(kind: ConversionErrorKind, message: impl Into<String>) -> Self {
+        ConversionError {
+            kind,
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    /// Create a ConversionError caused by another error.
+    pub fn with_source(
+        kind: ConversionErrorKind,
+        message: impl Into<String>,
+        source: impl std::error::Error + Send + Sync + 'static,
+    ) -> Self {
+        ConversionError {
+            kind,
+            message: message.into(),
+            source: Some(Box::new(source)),
+        }
+    }
+
+    /// A unit that could not be parsed, with the unit the user probably meant.
+    pub fn with_suggestion(invalid: &str, suggestion: Option<&str>) -> Self {
+        let message = match suggestion {
+            Some(suggestion) => format!(
+                "Invalid unit: '{}'. Did you mean '{}'?",
+                invalid, suggestion
+            ),
+            None => format!("Invalid unit: '{}'", invalid),
+        };
+        ConversionError::new(ConversionErrorKind::ParseError, message)
+    }
+
+    /// A unit name matching several units, e.g. "Ambiguous unit 't'. Did you mean: tonne (t), time (t)?".
+    pub fn ambiguous_unit(name: &str, matches: &[String]) -> Self {
+        ConversionError::new(
+            ConversionErrorKind::ParseError,
+            format!(
+                "Ambiguous unit '{}'. Did you mean: {}?",
+                name,
+                matches.join(", ")
+            ),
+        )
+    }
+
+    /// The units are of different categories.
+    pub fn unsupported_conversion(from: &Unit, to: &Unit) -> Self {
+        ConversionError::new(
+            ConversionErrorKind::UnsupportedConversion,
+            format!("Cannot convert from {} to {}", from, to),
+        )
+    }
+
+    /// The value could not be computed, e.g. after a division by zero.
+    pub fn missing_value() -> Self {
+        ConversionError::new(ConversionErrorKind::UnsupportedConversion, "Value is None")
+    }
+
+    /// The value lies outside of `[min, max]`.
+    pub fn out_of_range(value: impl Display, min: impl Display, max: impl Display) -> Self {
+        ConversionError::new(
+            ConversionErrorKind::OutOfRange,
+            format!("{} is outside of the range [{}, {}]", value, min, max),
+        )
+    }
+
+    /// Category of the error.
+    pub fn kind(&self) -> ConversionErrorKind {
+        self.kind
+    }
+
+    /// Description of the error, without the "Conversion error" prefix.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl PartialEq for ConversionError {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.message == other.message
+    }
 }
 
 impl Display for ConversionError {
@@ -23,9 +134,23 @@ impl Display for ConversionError {
     }
 }
 
-type ConversionResult<T> = Result<T, ConversionError>;
+impl std::error::Error for ConversionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|e| e as &(dyn std::error::Error + 'static))
+    }
+}
+
+pub type ConversionResult<T> = Result<T, ConversionError>;
+
+/// Significant digits of `Value::display_auto_precision`, see `Unitlike::precision`.
+const DEFAULT_PRECISION: u32 = 10;
+
+/// Relative tolerance of `Value::approx_eq`.
+const APPROX_EQ_TOLERANCE: f64 = 1e-9;
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
 pub struct Value {
     value: Option<f64>,
     unit: Unit,
@@ -39,352 +164,4666 @@ impl Value {
         }
     }
 
+    /// Numeric value, `None` if it could not be computed.
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
     pub fn convert_to(&self, to: &Unit) -> ConversionResult<Value> {
-        self.value.ok_or(ConversionError {
-            message: "Value is None".to_string(),
-        })?;
-        if self.unit != *to {
-            return Err(ConversionError {
-                message: format!("Cannot convert from {} to {}", self.unit, to),
-            });
+        self.convert_to_with(to, ExecutionContext::global())
+    }
+
+    /// Convert the value, looking up exchange rates in the cache of the given context.
+    pub fn convert_to_with(&self, to: &Unit, ctx: &ExecutionContext) -> ConversionResult<Value> {
+        self.value.ok_or_else(ConversionError::missing_value)?;
+        if self.unit.category() != to.category() {
+            return Err(ConversionError::unsupported_conversion(&self.unit, to));
         }
 
-        let new_value = Unit::convert(self.value.unwrap(), &self.unit, to)?;
-        Ok(Value {
+        let new_value = Unit::convert(self.value.unwrap(), &self.unit, to, ctx)?;
+        let converted = Value {
             value: Some(new_value),
-            unit: (*to).clone(),
-        })
+            unit: *to,
+        };
+        if let Some((min, max)) = to.physical_range() {
+            converted.assert_in_range(min, max)?;
+        }
+        Ok(converted)
     }
-}
 
-impl Display for Value {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.value {
-            Some(v) => write!(f, "{} {}", v, self.unit),
-            None => write!(f, "None {}", self.unit),
+    /// Convert the value to the SI unit of its category, see `Unit::si_base`.
+    pub fn to_si(&self) -> ConversionResult<Value> {
+        self.convert_to(&self.unit.si_base())
+    }
+
+    /// Whether `other` converted to the unit of `self` differs by less than the relative tolerance.
+    /// Fails if the values are of different categories.
+    pub fn is_approximately_equal(
+        &self,
+        other: &Value,
+        relative_tolerance: f64,
+    ) -> ConversionResult<bool> {
+        let a = self.value.ok_or_else(ConversionError::missing_value)?;
+        let b = other.convert_to(&self.unit)?.value.unwrap();
+        if a == 0.0 {
+            // no magnitude to be relative to
+            return Ok(b.abs() < relative_tolerance);
         }
+        Ok((a - b).abs() / a.abs() < relative_tolerance)
     }
-}
 
-#[derive(Debug, Clone, Copy, EnumIter)]
-pub enum Unit {
-    Length(LengthUnit),
-    Mass(MassUnit),
-    Currency(CurrencyUnit),
-}
+    /// `is_approximately_equal` with a relative tolerance of 1e-9.
+    pub fn approx_eq(&self, other: &Value) -> ConversionResult<bool> {
+        self.is_approximately_equal(other, APPROX_EQ_TOLERANCE)
+    }
 
-impl Unit {
-    fn convert(value: f64, from: &Unit, to: &Unit) -> ConversionResult<f64> {
-        match (from, to) {
-            (Unit::Length(from), Unit::Length(to)) => LengthUnit::convert(value, from, to),
-            (Unit::Mass(from), Unit::Mass(to)) => MassUnit::convert(value, from, to),
-            (Unit::Currency(from), Unit::Currency(to)) => CurrencyUnit::convert(value, from, to),
-            _ => Err(ConversionError {
-                message: format!("Cannot convert from {} to {}", from, to),
-            }),
+    /// Copy of the value if it lies within `[min, max]`, an `OutOfRange` error otherwise.
+    pub fn clamp(&self, min: f64, max: f64) -> ConversionResult<Value> {
+        self.assert_in_range(min, max).copied()
+    }
+
+    /// Check that the value lies within `[min, max]`, returning it for chaining.
+    pub fn assert_in_range(&self, min: f64, max: f64) -> ConversionResult<&Self> {
+        let value = self.value.ok_or_else(ConversionError::missing_value)?;
+        if value < min || value > max {
+            return Err(ConversionError::out_of_range(
+                self.display_compact(),
+                min,
+                max,
+            ));
         }
+        Ok(self)
     }
 
-    pub fn get_all_units() -> Vec<Unit> {
-        Unit::iter()
-            .flat_map(|unit| match unit {
-                Unit::Length(_) => LengthUnit::iter().map(Unit::Length).collect::<Vec<Unit>>(),
-                Unit::Mass(_) => MassUnit::iter().map(Unit::Mass).collect::<Vec<Unit>>(),
-                Unit::Currency(_) => CurrencyUnit::iter()
-                    .map(Unit::Currency)
-                    .collect::<Vec<Unit>>(),
-            })
-            .collect()
+    /// Machine-readable representation, e.g. `{"value": 1.0, "unit": "meter (m)"}`.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({ "value": self.value, "unit": self.unit.to_string() })
     }
 }
 
-impl PartialEq for Unit {
-    fn eq(&self, other: &Self) -> bool {
-        mem::discriminant(self) == mem::discriminant(other)
+impl Value {
+    /// Numeric values of `self` and `other`, with `other` expressed in the unit of `self`.
+    fn operands(&self, other: &Value, ctx: &ExecutionContext) -> ConversionResult<(f64, f64)> {
+        let a = self.value.ok_or_else(ConversionError::missing_value)?;
+        let b = other.convert_to_with(&self.unit, ctx)?.value.unwrap();
+        Ok((a, b))
     }
-}
 
-impl Display for Unit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Unit::Length(u) => write!(f, "{}", u),
-            Unit::Mass(u) => write!(f, "{}", u),
-            Unit::Currency(u) => write!(f, "{}", u),
-        }
+    /// Add two values like `+`, looking up exchange rates in the cache of the given context.
+    pub fn add_with(&self, other: &Value, ctx: &ExecutionContext) -> ConversionResult<Value> {
+        let (a, b) = self.operands(other, ctx)?;
+        Ok(Value::new(a + b, self.unit))
     }
-}
 
-impl FromStr for Unit {
-    type Err = String;
+    /// Subtract two values like `-`, looking up exchange rates in the cache of the given context.
+    pub fn sub_with(&self, other: &Value, ctx: &ExecutionContext) -> ConversionResult<Value> {
+        let (a, b) = self.operands(other, ctx)?;
+        Ok(Value::new(a - b, self.unit))
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if let Ok(length_unit) = s.parse::<LengthUnit>() {
-            return Ok(Unit::Length(length_unit));
-        }
-        if let Ok(mass_unit) = s.parse::<MassUnit>() {
-            return Ok(Unit::Mass(mass_unit));
+    /// Multiply the value by `factor`, keeping the unit.
+    pub fn scale(&self, factor: f64) -> Value {
+        Value {
+            value: self.value.map(|value| value * factor),
+            unit: self.unit,
         }
-        if let Ok(currency_unit) = s.parse::<CurrencyUnit>() {
-            return Ok(Unit::Currency(currency_unit));
+    }
+
+    /// Flip the sign of the value, fails for units without negative values.
+    pub fn negate(&self) -> ConversionResult<Value> {
+        if !self.unit.allows_negative() {
+            return Err(ConversionError::new(
+                ConversionErrorKind::UnsupportedConversion,
+                format!("{} cannot be negative", self.unit),
+            ));
         }
-        Err(format!("Invalid unit: {}", s))
+        Ok(self.scale(-1.0))
     }
 }
 
-trait Convertable {
-    fn to_base_unit(&self, value: f64) -> ConversionResult<f64>;
-    fn from_base_unit(&self, value: f64) -> ConversionResult<f64> {
-        let base_value = self.to_base_unit(1.0)?;
-        Ok(value / base_value)
-    }
-    fn convert(value: f64, from: &Self, to: &Self) -> ConversionResult<f64> {
-        let base_value = from.to_base_unit(value)?;
-        to.from_base_unit(base_value)
+impl Value {
+    /// Compare two values of the same category, e.g. 1 mi is greater than 1 km.
+    /// Both are converted to the SI unit of their category before comparing.
+    pub fn compare_to(&self, other: &Value) -> ConversionResult<Ordering> {
+        if self.unit.category() != other.unit.category() {
+            return Err(ConversionError::unsupported_conversion(
+                &self.unit,
+                &other.unit,
+            ));
+        }
+        let a = self.to_si()?.value.unwrap();
+        let b = other.to_si()?.value.unwrap();
+        Ok(a.total_cmp(&b))
     }
 }
 
-trait Unitlike:
-    Display + PartialEq + Convertable + FromStr + default::Default + IntoEnumIterator + Clone + Copy
-{
-    fn get_display_map() -> HashMap<(&'static str, &'static str), Self>;
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let display_map = Self::get_display_map();
-        let (long, short) = display_map.iter().find(|(_, &v)| v == *self).unwrap().0;
-        write!(f, "{} ({})", long, short)
+/// Orders values of the same category by their magnitude, see `Value::compare_to`.
+/// Values of different categories are not comparable.
+/// Note that equal magnitudes in different units, like 1 km and 1000 m, are not `==`.
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.compare_to(other).ok()
     }
+}
 
-    fn from_str(s: &str) -> Result<Self, String> {
-        Self::get_display_map()
-            .iter()
-            .find(|&((long, short), _)| s == *long || s == *short)
-            .map(|(_, &unit)| unit)
-            .ok_or_else(|| format!("Invalid unit: {}", s))
+/// Values are only equal in the same unit, so `Value`s can be used as `HashMap` keys.
+/// NaN values violate the reflexivity of `Eq`, as `NaN != NaN`.
+impl Eq for Value {}
+
+impl Hash for Value {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // the bit pattern hashes NaN consistently although it is never equal to itself,
+        // -0.0 is hashed like 0.0 as the two compare equal
+        self.value
+            .map(|v| if v == 0.0 { 0.0f64 } else { v }.to_bits())
+            .hash(state);
+        self.unit.hash(state);
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, EnumIter, Default)]
-pub enum LengthUnit {
-    #[default]
-    Meter,
-    Centimeter,
-    Kilometer,
-    Yard,
-    Foot,
-    Inch,
-}
+/// Adds two values of the same category, the result is expressed in the unit of the left operand.
+impl Add<&Value> for &Value {
+    type Output = ConversionResult<Value>;
 
-impl Unitlike for LengthUnit {
-    fn get_display_map() -> HashMap<(&'static str, &'static str), LengthUnit> {
-        let mut m = HashMap::new();
-        m.insert(("meter", "m"), LengthUnit::Meter);
-        m.insert(("centimeter", "cm"), LengthUnit::Centimeter);
-        m.insert(("kilometer", "km"), LengthUnit::Kilometer);
-        m.insert(("yard", "yd"), LengthUnit::Yard);
-        m.insert(("foot", "ft"), LengthUnit::Foot);
-        m.insert(("inch", "in"), LengthUnit::Inch);
-        m
+    fn add(self, other: &Value) -> Self::Output {
+        self.add_with(other, ExecutionContext::global())
     }
 }
 
-impl Display for LengthUnit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Unitlike::fmt(self, f)
+impl Add for Value {
+    type Output = ConversionResult<Value>;
+
+    fn add(self, other: Value) -> Self::Output {
+        <&Value as Add<&Value>>::add(&self, &other)
     }
 }
 
-impl FromStr for LengthUnit {
-    type Err = String;
+/// Subtracts two values of the same category, the result is expressed in the unit of the left operand.
+impl Sub<&Value> for &Value {
+    type Output = ConversionResult<Value>;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Unitlike::from_str(s)
+    fn sub(self, other: &Value) -> Self::Output {
+        self.sub_with(other, ExecutionContext::global())
     }
 }
 
-impl Convertable for LengthUnit {
-    fn to_base_unit(&self, value: f64) -> ConversionResult<f64> {
-        let val = match self {
-            LengthUnit::Meter => value,
-            LengthUnit::Centimeter => value / 100.0,
-            LengthUnit::Kilometer => value * 1000.0,
-            LengthUnit::Yard => value * 0.9144,
-            LengthUnit::Foot => value * 0.3048,
-            LengthUnit::Inch => value * 0.0254,
-        };
-        Ok(val)
+impl Sub for Value {
+    type Output = ConversionResult<Value>;
+
+    fn sub(self, other: Value) -> Self::Output {
+        <&Value as Sub<&Value>>::sub(&self, &other)
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, EnumIter, Default)]
-pub enum MassUnit {
-    #[default]
-    Kilogram,
-    Gram,
-    Ton,
-    Pound,
-    Ounce,
-}
+/// Scales the value, keeping its unit.
+impl Mul<f64> for &Value {
+    type Output = Value;
 
-impl Unitlike for MassUnit {
-    fn get_display_map() -> HashMap<(&'static str, &'static str), MassUnit> {
-        let mut m = HashMap::new();
-        m.insert(("kilogram", "kg"), MassUnit::Kilogram);
-        m.insert(("gram", "g"), MassUnit::Gram);
-        m.insert(("ton", "t"), MassUnit::Ton);
-        m.insert(("pound", "lb"), MassUnit::Pound);
-        m.insert(("ounce", "oz"), MassUnit::Ounce);
-        m
+    fn mul(self, factor: f64) -> Self::Output {
+        self.scale(factor)
     }
 }
 
-impl Display for MassUnit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        Unitlike::fmt(self, f)
+impl Mul<f64> for Value {
+    type Output = Value;
+
+    fn mul(self, factor: f64) -> Self::Output {
+        <&Value as Mul<f64>>::mul(&self, factor)
     }
 }
 
-impl FromStr for MassUnit {
-    type Err = String;
+/// Divides the value, keeping its unit. Dividing by zero yields a value of `None`.
+impl Div<f64> for &Value {
+    type Output = Value;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Unitlike::from_str(s)
+    fn div(self, divisor: f64) -> Self::Output {
+        if divisor == 0.0 {
+            return Value {
+                value: None,
+                unit: self.unit,
+            };
+        }
+        self.scale(1.0 / divisor)
     }
 }
 
-impl Convertable for MassUnit {
-    fn to_base_unit(&self, value: f64) -> ConversionResult<f64> {
-        let val = match self {
-            MassUnit::Kilogram => value,
-            MassUnit::Gram => value / 1000.0,
-            MassUnit::Ton => value * 1000.0,
-            MassUnit::Pound => value * 0.453592,
-            MassUnit::Ounce => value * 0.0283495,
-        };
-        Ok(val)
+impl Div<f64> for Value {
+    type Output = Value;
+
+    fn div(self, divisor: f64) -> Self::Output {
+        <&Value as Div<f64>>::div(&self, divisor)
     }
 }
 
-#[derive(Debug, PartialEq, Clone, Copy, EnumIter, Default, Hash, Eq)]
-pub enum CurrencyUnit {
-    #[default]
-    USD,
-    EUR,
-    JPY,
-    KRW,
-    GBP,
-    AUD,
+/// Value at fraction `t` (0.0 to 1.0) of the way from `start` to `end`, in the unit of `start`.
+/// Both values must be of the same category, they are interpolated in its SI unit.
+pub fn interpolate(t: f64, start: &Value, end: &Value) -> ConversionResult<Value> {
+    interpolate_with(t, start, end, ExecutionContext::global())
 }
 
-impl Unitlike for CurrencyUnit {
-    fn get_display_map() -> HashMap<(&'static str, &'static str), CurrencyUnit> {
-        let mut m = HashMap::new();
-        m.insert(("USD", "USD"), CurrencyUnit::USD);
-        m.insert(("EUR", "EUR"), CurrencyUnit::EUR);
-        m.insert(("JPY", "JPY"), CurrencyUnit::JPY);
-        m.insert(("KRW", "KRW"), CurrencyUnit::KRW);
-        m.insert(("GBP", "GBP"), CurrencyUnit::GBP);
-        m.insert(("AUD", "AUD"), CurrencyUnit::AUD);
-        m
+/// Interpolate like `interpolate`, looking up exchange rates in the cache of the given context.
+pub(crate) fn interpolate_with(
+    t: f64,
+    start: &Value,
+    end: &Value,
+    ctx: &ExecutionContext,
+) -> ConversionResult<Value> {
+    if !(0.0..=1.0).contains(&t) {
+        return Err(ConversionError::out_of_range(
+            format!("{}%", t * 100.0),
+            "0%",
+            "100%",
+        ));
     }
+    let base = start.unit.si_base();
+    let a = start.convert_to_with(&base, ctx)?.value.unwrap();
+    let b = end.convert_to_with(&base, ctx)?.value.unwrap();
+    Value::new(a + t * (b - a), base).convert_to_with(&start.unit, ctx)
 }
 
-impl Display for CurrencyUnit {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let display_map = Self::get_display_map();
-        let (long, _) = display_map.iter().find(|(_, &v)| v == *self).unwrap().0;
-        write!(f, "{}", long)
-    }
+/// Units a value is converted through one after another, e.g. kg -> lb -> oz.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionChain {
+    steps: Vec<Unit>,
 }
 
-impl FromStr for CurrencyUnit {
-    type Err = String;
+impl ConversionChain {
+    /// Chain of at least two units, each convertible to the next one.
+    pub fn new(units: Vec<Unit>) -> Result<Self, ConversionError> {
+        if units.len() < 2 {
+            return Err(ConversionError::new(
+                ConversionErrorKind::ParseError,
+                "A conversion chain needs at least two units",
+            ));
+        }
+        if let Some(pair) = units
+            .windows(2)
+            .find(|pair| pair[0].category() != pair[1].category())
+        {
+            return Err(ConversionError::unsupported_conversion(&pair[0], &pair[1]));
+        }
+        Ok(ConversionChain { steps: units })
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Unitlike::from_str(s)
+    pub fn steps(&self) -> &[Unit] {
+        &self.steps
     }
-}
 
-impl Convertable for CurrencyUnit {
-    fn to_base_unit(&self, value: f64) -> ConversionResult<f64> {
-        CACHE
-            .lock()
-            .unwrap()
-            .get_base_rate(*self)
-            .map(|rate| value / rate)
-            .map_err(|e| ConversionError {
-                message: e.to_string(),
-            })
+    /// Convert a value in the first unit through all steps, returning the value of every step.
+    pub fn apply(&self, value: f64) -> ConversionResult<Vec<Value>> {
+        self.iter(value).collect()
     }
-}
 
-// test eq of value
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Apply the chain like `apply`, looking up exchange rates in the cache of the given context.
+    pub(crate) fn apply_with(
+        &self,
+        value: f64,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<Vec<Value>> {
+        self.iter_with(value, ctx).collect()
+    }
 
-    #[test]
-    fn test_value_eq() {
-        let v1 = Value {
-            value: Some(1.0),
-            unit: Unit::Length(LengthUnit::Meter),
-        };
-        let v2 = Value {
-            value: Some(1.0),
-            unit: Unit::Length(LengthUnit::Meter),
-        };
+    /// Values of the steps, computed one at a time.
+    pub fn iter(&self, value: f64) -> ChainValues<'_> {
+        self.iter_with(value, ExecutionContext::global())
+    }
 
-        let v3 = Value {
-            value: Some(1.0),
-            unit: Unit::Length(LengthUnit::Kilometer),
-        };
+    fn iter_with<'a>(&'a self, value: f64, ctx: &'a ExecutionContext) -> ChainValues<'a> {
+        ChainValues {
+            steps: self.steps.iter(),
+            current: Some(Value::new(value, self.steps[0])),
+            ctx,
+        }
+    }
+}
 
-        let v4 = Value {
-            value: Some(2.0),
-            unit: Unit::Length(LengthUnit::Meter),
-        };
+/// Iterator over the values of a `ConversionChain`, it stops after the first failed step.
+#[derive(Debug)]
+pub struct ChainValues<'a> {
+    steps: std::slice::Iter<'a, Unit>,
+    current: Option<Value>,
+    ctx: &'a ExecutionContext,
+}
 
-        assert_eq!(v1, v2);
-        assert_eq!(v1, v3);
-        assert_ne!(v1, v4);
+impl Iterator for ChainValues<'_> {
+    type Item = ConversionResult<Value>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.current.take()?;
+        let result = current.convert_to_with(self.steps.next()?, self.ctx);
+        self.current = result.as_ref().ok().copied();
+        Some(result)
     }
+}
 
-    #[test]
-    fn test_unit_eq() {
-        let u1 = Unit::Length(LengthUnit::Meter);
-        let u2 = Unit::Length(LengthUnit::Meter);
-        assert_eq!(u1, u2);
+impl Value {
+    /// Parse a sum of values like "5 ft 11 in" or "2 m 30 cm", expressed in the first unit.
+    pub fn from_compound_str(s: &str) -> ConversionResult<Value> {
+        Value::sum_with(&Value::parse_compound(s)?, ExecutionContext::global())
+    }
 
-        let u3 = Unit::Length(LengthUnit::Kilometer);
-        assert_eq!(u1, u3);
+    /// Parse the values of a sum like "5 ft 11 in", which must all be of one category.
+    /// Unlike `from_compound_str` nothing is converted, so no exchange rates are requested.
+    pub fn parse_compound(s: &str) -> ConversionResult<Vec<Value>> {
+        let tokens: Vec<&str> = s.split_whitespace().collect();
+        if tokens.is_empty() || !tokens.len().is_multiple_of(2) {
+            return Err(ConversionError::new(
+                ConversionErrorKind::ParseError,
+                format!("Expected <value> <unit> pairs, got: {}", s),
+            ));
+        }
 
-        let u4 = Unit::Mass(MassUnit::Kilogram);
-        assert_ne!(u1, u4);
+        let values = tokens
+            .chunks(2)
+            .map(|pair| pair.join(" ").parse::<Value>())
+            .collect::<ConversionResult<Vec<Value>>>()?;
+        let first = values[0].unit;
+        match values
+            .iter()
+            .find(|v| v.unit.category() != first.category())
+        {
+            Some(v) => Err(ConversionError::unsupported_conversion(&v.unit, &first)),
+            None => Ok(values),
+        }
     }
 
-    #[test]
-    fn test_length_conversion() {
-        let v = Value::new(1.0, Unit::Length(LengthUnit::Meter));
-        let v2 = v.convert_to(&Unit::Length(LengthUnit::Kilometer)).unwrap();
-        assert_eq!(v2, Value::new(0.001, Unit::Length(LengthUnit::Kilometer)));
+    /// Sum of the values in the unit of the first one, looking up exchange rates in the
+    /// cache of the given context.
+    pub fn sum_with(values: &[Value], ctx: &ExecutionContext) -> ConversionResult<Value> {
+        let (first, rest) = values
+            .split_first()
+            .ok_or_else(ConversionError::missing_value)?;
+        rest.iter().try_fold(*first, |sum, v| sum.add_with(v, ctx))
     }
+}
 
-    #[test]
-    fn test_length_conversion_edge_case() {
-        let v = Value::new(0.0, Unit::Length(LengthUnit::Meter));
-        let v2 = v.convert_to(&Unit::Length(LengthUnit::Kilometer)).unwrap();
-        assert_eq!(v2, Value::new(0.0, Unit::Length(LengthUnit::Kilometer)));
+impl Value {
+    /// Parse a value whose number is written in the given numeral system, e.g. "0x400 B".
+    /// The prefix of the numeral system is optional.
+    pub fn from_str_with_base(s: &str, base: NumeralSystem) -> ConversionResult<Value> {
+        let (value, unit) = s.trim().split_once(char::is_whitespace).ok_or_else(|| {
+            ConversionError::new(
+                ConversionErrorKind::ParseError,
+                format!("Expected <value> <unit>, got: {}", s),
+            )
+        })?;
+        let value = base.parse(value)?;
+        Ok(Value::new(value, Unit::parse_unambiguous(unit.trim())?))
+    }
+}
+
+impl FromStr for Value {
+    type Err = ConversionError;
+
+    /// Parse a value with its unit, e.g. "100 km", "-5 °C" or "0x400 B".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number = s.split_whitespace().next().unwrap_or_default();
+        Value::from_str_with_base(s, NumeralSystem::detect(number))
+    }
+}
+
+/// `let v: Value = (100.0, "km").try_into()?;`
+impl TryFrom<(f64, &str)> for Value {
+    type Error = ConversionError;
+
+    fn try_from((value, unit): (f64, &str)) -> Result<Self, Self::Error> {
+        let unit = Unit::lookup(unit, true)
+            .or_else(|| Unit::lookup(unit, false))
+            .ok_or_else(|| ConversionError::with_suggestion(unit, suggest_unit(unit)))?;
+        Ok(Value::new(value, unit))
+    }
+}
+
+impl TryFrom<&str> for Value {
+    type Error = ConversionError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// The number of the value, NaN if it could not be computed.
+impl From<Value> for f64 {
+    fn from(value: Value) -> Self {
+        value.value.unwrap_or(f64::NAN)
+    }
+}
+
+/// The number and unit of the value, NaN if the number could not be computed.
+impl From<Value> for (f64, Unit) {
+    fn from(value: Value) -> Self {
+        (value.into(), value.unit)
+    }
+}
+
+/// Base a number is written in, all but decimal numbers are prefixed with "0b", "0o" or "0x".
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum NumeralSystem {
+    #[default]
+    Decimal,
+    Binary,
+    Octal,
+    Hexadecimal,
+}
+
+impl NumeralSystem {
+    pub fn radix(&self) -> u32 {
+        match self {
+            NumeralSystem::Decimal => 10,
+            NumeralSystem::Binary => 2,
+            NumeralSystem::Octal => 8,
+            NumeralSystem::Hexadecimal => 16,
+        }
+    }
+
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            NumeralSystem::Decimal => "",
+            NumeralSystem::Binary => "0b",
+            NumeralSystem::Octal => "0o",
+            NumeralSystem::Hexadecimal => "0x",
+        }
+    }
+
+    /// Numeral system of a number by its prefix, e.g. hexadecimal for "0x1F" or "-0x1F".
+    pub fn detect(s: &str) -> NumeralSystem {
+        let digits = s.strip_prefix('-').unwrap_or(s);
+        [
+            NumeralSystem::Binary,
+            NumeralSystem::Octal,
+            NumeralSystem::Hexadecimal,
+        ]
+        .into_iter()
+        .find(|system| {
+            digits
+                .get(..2)
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(system.prefix()))
+        })
+        .unwrap_or(NumeralSystem::Decimal)
+    }
+
+    /// Parse a number of this system, the prefix is optional.
+    /// Only decimal numbers can have a fraction or an exponent.
+    pub fn parse(&self, s: &str) -> ConversionResult<f64> {
+        let invalid = || format!("Invalid value: {}", s);
+        if *self == NumeralSystem::Decimal {
+            return s.parse().map_err(|e| {
+                ConversionError::with_source(ConversionErrorKind::ParseError, invalid(), e)
+            });
+        }
+
+        let (sign, digits) = match s.strip_prefix('-') {
+            Some(digits) => (-1.0, digits),
+            None => (1.0, s),
+        };
+        let digits = match digits.get(..2) {
+            Some(prefix) if prefix.eq_ignore_ascii_case(self.prefix()) => &digits[2..],
+            _ => digits,
+        };
+        u64::from_str_radix(digits, self.radix())
+            .map(|value| sign * value as f64)
+            .map_err(|e| {
+                ConversionError::with_source(ConversionErrorKind::ParseError, invalid(), e)
+            })
+    }
+
+    /// Write a whole number in this system, e.g. "0x400". `None` for fractions and decimal numbers.
+    pub fn format(&self, value: f64) -> Option<String> {
+        // larger numbers are not exact in a f64
+        const MAX_EXACT: f64 = (1u64 << 53) as f64;
+        if *self == NumeralSystem::Decimal || value.fract() != 0.0 || value.abs() > MAX_EXACT {
+            return None;
+        }
+        let sign = if value < 0.0 { "-" } else { "" };
+        let magnitude = value.abs() as u64;
+        let digits = match self {
+            NumeralSystem::Binary => format!("{:b}", magnitude),
+            NumeralSystem::Octal => format!("{:o}", magnitude),
+            _ => format!("{:x}", magnitude),
+        };
+        Some(format!("{}{}{}", sign, self.prefix(), digits))
+    }
+}
+
+/// Options controlling how values are rendered for the user.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FormatOptions {
+    /// Show the full unit name instead of only its abbreviation.
+    pub verbose: bool,
+    /// Number of decimal places, `None` prints the shortest exact representation.
+    pub precision: Option<usize>,
+    /// Numeral system whole numbers are written in, fractions are always decimal.
+    pub base: NumeralSystem,
+}
+
+impl Value {
+    /// Display currencies with their symbol, e.g. "$5" or "5 kr", other values like `display_compact`.
+    pub fn display_with_symbol(&self) -> String {
+        let Unit::Currency(currency) = self.unit else {
+            return self.display_compact().to_string();
+        };
+        let value = format_number(self.value, None);
+        match currency.symbol() {
+            // letters would run into the number
+            symbol if symbol.chars().all(char::is_alphabetic) => format!("{} {}", value, symbol),
+            symbol => format!("{}{}", symbol, value),
+        }
+    }
+
+    /// Display currencies with their name, e.g. "5 Euro", other values like `display_compact`.
+    pub fn display_full_currency(&self) -> String {
+        match self.unit {
+            Unit::Currency(currency) => {
+                format!(
+                    "{} {}",
+                    format_number(self.value, None),
+                    currency.full_name()
+                )
+            }
+            _ => self.display_compact().to_string(),
+        }
+    }
+
+    /// Display the value with all significant digits of its unit, e.g. "1.000000000 km".
+    pub fn display_auto_precision(&self) -> String {
+        let digits = self.unit.precision().max(1) as i32;
+        let decimals = match self.value {
+            Some(v) if v != 0.0 && v.is_finite() => digits - 1 - v.abs().log10().floor() as i32,
+            _ => digits - 1,
+        };
+        self.with_precision(decimals.max(0) as usize).to_string()
+    }
+
+    /// Display the value with the unit abbreviation, e.g. "1 km".
+    pub fn display_compact(&self) -> impl Display + '_ {
+        ValueDisplay {
+            value: self,
+            verbose: false,
+            precision: None,
+            base: NumeralSystem::Decimal,
+        }
+    }
+
+    /// Display the value with the full unit name, e.g. "1 kilometer (km)".
+    pub fn display_verbose(&self) -> impl Display + '_ {
+        ValueDisplay {
+            value: self,
+            verbose: true,
+            precision: None,
+            base: NumeralSystem::Decimal,
+        }
+    }
+
+    /// Display the value rounded to a fixed number of decimal places.
+    pub fn with_precision(self, decimals: usize) -> FormattedValue {
+        FormattedValue {
+            value: self,
+            precision: decimals,
+            base: NumeralSystem::Decimal,
+        }
+    }
+
+    /// Render the value according to the given options.
+    pub fn format(&self, options: &FormatOptions) -> String {
+        ValueDisplay {
+            value: self,
+            verbose: options.verbose,
+            precision: options.precision,
+            base: options.base,
+        }
+        .to_string()
+    }
+}
+
+/// A value displayed with a fixed number of decimal places, e.g. "0.91 m".
+#[derive(Debug, Clone, Copy)]
+pub struct FormattedValue {
+    value: Value,
+    precision: usize,
+    base: NumeralSystem,
+}
+
+impl FormattedValue {
+    /// Write whole numbers in another numeral system, e.g. "0x400 B".
+    pub fn in_base(self, base: NumeralSystem) -> FormattedValue {
+        FormattedValue { base, ..self }
+    }
+}
+
+impl Display for FormattedValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display = ValueDisplay {
+            value: &self.value,
+            verbose: false,
+            precision: Some(self.precision),
+            base: self.base,
+        };
+        write!(f, "{}", display)
+    }
+}
+
+struct ValueDisplay<'a> {
+    value: &'a Value,
+    verbose: bool,
+    precision: Option<usize>,
+    base: NumeralSystem,
+}
+
+/// Format a number with the given decimal places, or its shortest exact representation.
+fn format_number(value: Option<f64>, precision: Option<usize>) -> String {
+    match (value, precision) {
+        (Some(v), Some(prec)) => format!("{:.prec$}", v, prec = prec),
+        (Some(v), None) => v.to_string(),
+        (None, _) => "None".to_string(),
+    }
+}
+
+impl Display for ValueDisplay<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let number = self.value.value;
+        match number.and_then(|v| self.base.format(v)) {
+            Some(number) => write!(f, "{} ", number)?,
+            None => write!(f, "{} ", format_number(number, self.precision))?,
+        }
+        if self.verbose {
+            write!(f, "{}", self.value.unit)?;
+        } else {
+            write!(f, "{}", self.value.unit.abbreviation())?;
+        }
+        if self.value.unit.is_approximate() {
+            write!(f, " (approximate)")?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.display_compact())
+    }
+}
+
+/// System of measurement used to pick a readable unit, see `Value::display_natural`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum UnitSystem {
+    Metric,
+    Imperial,
+}
+
+/// Units a value is naturally expressed in, from the largest to the smallest.
+/// Rarely used units like the yard or the carat are left out.
+fn natural_units(category: UnitCategory, system: UnitSystem) -> &'static [Unit] {
+    use UnitSystem::*;
+    match (category, system) {
+        (UnitCategory::Length, Metric) => &[
+            Unit::Length(LengthUnit::Kilometer),
+            Unit::Length(LengthUnit::Meter),
+            Unit::Length(LengthUnit::Centimeter),
+            Unit::Length(LengthUnit::Millimeter),
+            Unit::Length(LengthUnit::Micrometer),
+            Unit::Length(LengthUnit::Nanometer),
+        ],
+        (UnitCategory::Length, Imperial) => &[
+            Unit::Length(LengthUnit::Mile),
+            Unit::Length(LengthUnit::Foot),
+            Unit::Length(LengthUnit::Inch),
+        ],
+        (UnitCategory::Mass, Metric) => &[
+            Unit::Mass(MassUnit::MetricTon),
+            Unit::Mass(MassUnit::Kilogram),
+            Unit::Mass(MassUnit::Gram),
+            Unit::Mass(MassUnit::Milligram),
+            Unit::Mass(MassUnit::Microgram),
+        ],
+        (UnitCategory::Mass, Imperial) => &[
+            Unit::Mass(MassUnit::ShortTon),
+            Unit::Mass(MassUnit::Pound),
+            Unit::Mass(MassUnit::Ounce),
+        ],
+        (UnitCategory::Volume, Metric) => &[
+            Unit::Volume(VolumeUnit::CubicMeter),
+            Unit::Volume(VolumeUnit::Liter),
+            Unit::Volume(VolumeUnit::Milliliter),
+        ],
+        (UnitCategory::Volume, Imperial) => &[
+            Unit::Volume(VolumeUnit::USGallon),
+            Unit::Volume(VolumeUnit::USFluidOunce),
+        ],
+        (UnitCategory::Area, Metric) => &[
+            Unit::Area(AreaUnit::SquareKilometer),
+            Unit::Area(AreaUnit::SquareMeter),
+            Unit::Area(AreaUnit::SquareCentimeter),
+            Unit::Area(AreaUnit::SquareMillimeter),
+        ],
+        (UnitCategory::Area, Imperial) => &[
+            Unit::Area(AreaUnit::SquareMile),
+            Unit::Area(AreaUnit::SquareFoot),
+            Unit::Area(AreaUnit::SquareInch),
+        ],
+        _ => &[],
+    }
+}
+
+/// Round to three decimal places, hiding floating point noise like 4.999999999.
+fn round_natural(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
+
+impl Value {
+    /// Display the value in the unit of `system` that gives a number between 1 and 999,
+    /// e.g. "1.5 km" for 1500 m. Imperial values are split into two units, e.g. "3 ft 6 in".
+    /// Values of categories without metric and imperial units are displayed unchanged.
+    pub fn display_natural(&self, system: UnitSystem) -> String {
+        let units = natural_units(self.unit.category(), system);
+        let converted: Vec<Value> = match units
+            .iter()
+            .map(|unit| self.convert_to(unit))
+            .collect::<ConversionResult<Vec<_>>>()
+        {
+            Ok(converted) if !converted.is_empty() => converted,
+            _ => return self.to_string(),
+        };
+        let magnitude = |value: &Value| value.value.unwrap().abs();
+
+        // the largest unit the value is at least 1 in, tiny values use the smallest unit
+        let index = if magnitude(&converted[0]) == 0.0 {
+            units
+                .iter()
+                .position(|unit| *unit == self.unit)
+                .unwrap_or(0)
+        } else {
+            converted
+                .iter()
+                .position(|value| magnitude(value) >= 1.0 - 1e-9)
+                .unwrap_or(converted.len() - 1)
+        };
+        let value = converted[index];
+
+        let smaller = match (system, converted.get(index + 1)) {
+            (UnitSystem::Imperial, Some(smaller)) => smaller,
+            _ => return Value::new(round_natural(value.value.unwrap()), value.unit).to_string(),
+        };
+        // split into whole larger units and the rest in the smaller unit
+        let sign = if value.value.unwrap() < 0.0 { "-" } else { "" };
+        let per_unit = magnitude(smaller) / magnitude(&value);
+        let total = round_natural(magnitude(smaller));
+        let whole = (total / per_unit + 1e-9).floor();
+        let rest = round_natural(total - whole * per_unit);
+        let whole = Value::new(whole, value.unit);
+        if rest == 0.0 {
+            format!("{}{}", sign, whole)
+        } else {
+            format!("{}{} {}", sign, whole, Value::new(rest, smaller.unit))
+        }
+    }
+}
+
+/// A value converted into every unit of its category.
+#[derive(Debug)]
+pub struct ConversionTable {
+    pub source: Value,
+    pub conversions: Vec<(Unit, ConversionResult<Value>)>,
+}
+
+impl Value {
+    /// Convert the value into all units of the same category.
+    pub fn to_conversion_table(&self) -> ConversionTable {
+        self.to_conversion_table_with(ExecutionContext::global())
+    }
+
+    /// Conversion table with exchange rates from the cache of the given context.
+    pub fn to_conversion_table_with(&self, ctx: &ExecutionContext) -> ConversionTable {
+        let conversions = Unit::iter_by_category(self.unit.category())
+            .map(|unit| (unit, self.convert_to_with(&unit, ctx)))
+            .collect();
+        ConversionTable {
+            source: *self,
+            conversions,
+        }
+    }
+}
+
+/// Decimal places of the values in a displayed `ConversionTable`.
+const TABLE_PRECISION: usize = 6;
+
+impl ConversionTable {
+    /// Render the table with one aligned row per unit according to the given options.
+    /// Unit names and abbreviations are left-aligned, the values right-aligned.
+    pub fn format(&self, options: &FormatOptions) -> String {
+        let rows: Vec<(&str, String, String)> = self
+            .conversions
+            .iter()
+            .map(|(unit, result)| {
+                let cell = match result {
+                    Ok(v) => format_number(v.value, options.precision),
+                    Err(_) => "<error>".to_string(),
+                };
+                (unit.full_name(), format!("({})", unit.abbreviation()), cell)
+            })
+            .collect();
+        let width = |column: fn(&(&str, String, String)) -> usize| {
+            rows.iter().map(column).max().unwrap_or(0)
+        };
+        let name_width = width(|row| row.0.chars().count());
+        let abbreviation_width = width(|row| row.1.chars().count());
+        let value_width = width(|row| row.2.chars().count());
+
+        // the source is shown as entered, only the converted values are rounded
+        let source = self.source.format(&FormatOptions {
+            precision: None,
+            ..*options
+        });
+        let mut output = format!("{} is equal to:", source);
+        for (name, abbreviation, cell) in rows {
+            output.push_str(&format!(
+                "\n  {:<name_width$} {:<abbreviation_width$}  {:>value_width$}",
+                name, abbreviation, cell
+            ));
+        }
+        output
+    }
+}
+
+/// The table with a fixed number of decimal places, e.g. "  kilometer  (km)  0.001000".
+impl Display for ConversionTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let options = FormatOptions {
+            precision: Some(TABLE_PRECISION),
+            ..Default::default()
+        };
+        write!(f, "{}", self.format(&options))
+    }
+}
+
+/// The physical quantity (or currency) a unit measures.
+/// Only units of the same category can be converted into each other.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter)]
+pub enum UnitCategory {
+    Length,
+    Mass,
+    Currency,
+    Temperature,
+    Volume,
+    Speed,
+    Pressure,
+    Data,
+    Time,
+    Energy,
+    Angle,
+    Area,
+    Power,
+    FuelEfficiency,
+    DataTransferRate,
+}
+
+impl Display for UnitCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            UnitCategory::Length => "Length",
+            UnitCategory::Mass => "Mass",
+            UnitCategory::Currency => "Currency",
+            UnitCategory::Temperature => "Temperature",
+            UnitCategory::Volume => "Volume",
+            UnitCategory::Speed => "Speed",
+            UnitCategory::Pressure => "Pressure",
+            UnitCategory::Data => "Data",
+            UnitCategory::Time => "Time",
+            UnitCategory::Energy => "Energy",
+            UnitCategory::Angle => "Angle",
+            UnitCategory::Area => "Area",
+            UnitCategory::Power => "Power",
+            UnitCategory::FuelEfficiency => "Fuel efficiency",
+            UnitCategory::DataTransferRate => "Data transfer rate",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for UnitCategory {
+    type Err = String;
+
+    /// Parse a category by its name, ignoring case, e.g. "length" or "fuel_efficiency".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim().replace(['_', '-'], " ");
+        UnitCategory::iter()
+            .find(|category| category.to_string().eq_ignore_ascii_case(&name))
+            .ok_or_else(|| format!("Invalid category: {}", s))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter)]
+pub enum Unit {
+    Length(LengthUnit),
+    Mass(MassUnit),
+    Currency(CurrencyUnit),
+    Temperature(TemperatureUnit),
+    Volume(VolumeUnit),
+    Speed(SpeedUnit),
+    Pressure(PressureUnit),
+    Data(DataUnit),
+    Time(TimeUnit),
+    Energy(EnergyUnit),
+    Angle(AngleUnit),
+    Area(AreaUnit),
+    Power(PowerUnit),
+    FuelEfficiency(FuelEfficiencyUnit),
+    DataTransferRate(DataTransferRateUnit),
+}
+
+impl Unit {
+    /// Convert a value between two units, linear units use a cached factor.
+    pub(crate) fn convert(
+        value: f64,
+        from: &Unit,
+        to: &Unit,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<f64> {
+        with_result_cache(|cache| cache.convert(value, from, to, ctx))
+    }
+
+    /// Convert a value through the base unit of its category, without the factor cache.
+    pub(crate) fn convert_uncached(
+        value: f64,
+        from: &Unit,
+        to: &Unit,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<f64> {
+        match (from, to) {
+            (Unit::Length(from), Unit::Length(to)) => LengthUnit::convert(value, from, to),
+            (Unit::Mass(from), Unit::Mass(to)) => MassUnit::convert(value, from, to),
+            (Unit::Currency(from), Unit::Currency(to)) => {
+                CurrencyUnit::convert_with(value, from, to, ctx)
+            }
+            (Unit::Temperature(from), Unit::Temperature(to)) => {
+                TemperatureUnit::convert(value, from, to)
+            }
+            (Unit::Volume(from), Unit::Volume(to)) => VolumeUnit::convert(value, from, to),
+            (Unit::Speed(from), Unit::Speed(to)) => SpeedUnit::convert(value, from, to),
+            (Unit::Pressure(from), Unit::Pressure(to)) => PressureUnit::convert(value, from, to),
+            (Unit::Data(from), Unit::Data(to)) => DataUnit::convert(value, from, to),
+            (Unit::Time(from), Unit::Time(to)) => TimeUnit::convert(value, from, to),
+            (Unit::Energy(from), Unit::Energy(to)) => EnergyUnit::convert(value, from, to),
+            (Unit::Angle(from), Unit::Angle(to)) => AngleUnit::convert(value, from, to),
+            (Unit::Area(from), Unit::Area(to)) => AreaUnit::convert(value, from, to),
+            (Unit::Power(from), Unit::Power(to)) => PowerUnit::convert(value, from, to),
+            (Unit::FuelEfficiency(from), Unit::FuelEfficiency(to)) => {
+                FuelEfficiencyUnit::convert(value, from, to)
+            }
+            (Unit::DataTransferRate(from), Unit::DataTransferRate(to)) => {
+                DataTransferRateUnit::convert(value, from, to)
+            }
+            _ => Err(ConversionError::unsupported_conversion(from, to)),
+        }
+    }
+
+    /// Whether negative values are meaningful, which is not the case for
+    /// absolute temperature scales, amounts of data and data transfer rates.
+    pub fn allows_negative(&self) -> bool {
+        !matches!(
+            self,
+            Unit::Temperature(TemperatureUnit::Kelvin | TemperatureUnit::Rankine)
+                | Unit::Data(_)
+                | Unit::DataTransferRate(_)
+        )
+    }
+
+    /// Canonical unit of the category: the SI base or derived unit, USD for currencies
+    /// (the base of the exchange rates) and the conversion base unit for categories without an SI unit.
+    pub fn si_base(&self) -> Unit {
+        match self {
+            Unit::Length(_) => Unit::Length(LengthUnit::Meter),
+            Unit::Mass(_) => Unit::Mass(MassUnit::Kilogram),
+            Unit::Currency(_) => Unit::Currency(CurrencyUnit::USD),
+            Unit::Temperature(_) => Unit::Temperature(TemperatureUnit::Kelvin),
+            Unit::Volume(_) => Unit::Volume(VolumeUnit::CubicMeter),
+            Unit::Speed(_) => Unit::Speed(SpeedUnit::MetersPerSecond),
+            Unit::Pressure(_) => Unit::Pressure(PressureUnit::Pascal),
+            Unit::Data(_) => Unit::Data(DataUnit::Bit),
+            Unit::Time(_) => Unit::Time(TimeUnit::Second),
+            Unit::Energy(_) => Unit::Energy(EnergyUnit::Joule),
+            Unit::Angle(_) => Unit::Angle(AngleUnit::Radian),
+            Unit::Area(_) => Unit::Area(AreaUnit::SquareMeter),
+            Unit::Power(_) => Unit::Power(PowerUnit::Watt),
+            Unit::FuelEfficiency(_) => Unit::FuelEfficiency(FuelEfficiencyUnit::KilometersPerLiter),
+            Unit::DataTransferRate(_) => {
+                Unit::DataTransferRate(DataTransferRateUnit::BitsPerSecond)
+            }
+        }
+    }
+
+    /// Range of physically possible values, `None` for units without bounds.
+    /// Temperatures cannot be below absolute zero.
+    pub fn physical_range(&self) -> Option<(f64, f64)> {
+        let min = match self {
+            Unit::Temperature(TemperatureUnit::Kelvin | TemperatureUnit::Rankine) => 0.0,
+            Unit::Temperature(TemperatureUnit::Celsius) => -273.15,
+            Unit::Temperature(TemperatureUnit::Fahrenheit) => -459.67,
+            _ => return None,
+        };
+        Some((min, f64::INFINITY))
+    }
+
+    /// Whether the unit is only defined as an average, e.g. months and years.
+    pub fn is_approximate(&self) -> bool {
+        matches!(self, Unit::Time(TimeUnit::Month | TimeUnit::Year))
+    }
+
+    /// Whether the unit belongs to the metric system.
+    /// Only length, mass, temperature, volume, speed and area units are classified,
+    /// units like the knot or the nautical mile are neither metric nor imperial.
+    pub fn is_metric(&self) -> bool {
+        matches!(
+            self,
+            Unit::Length(
+                LengthUnit::Meter
+                    | LengthUnit::Centimeter
+                    | LengthUnit::Kilometer
+                    | LengthUnit::Millimeter
+                    | LengthUnit::Micrometer
+                    | LengthUnit::Nanometer
+                    | LengthUnit::Picometer
+            ) | Unit::Mass(
+                MassUnit::Kilogram
+                    | MassUnit::Gram
+                    | MassUnit::Milligram
+                    | MassUnit::Microgram
+                    | MassUnit::Nanogram
+                    | MassUnit::MetricTon
+                    | MassUnit::Carat
+            ) | Unit::Temperature(TemperatureUnit::Kelvin | TemperatureUnit::Celsius)
+                | Unit::Volume(
+                    VolumeUnit::Liter
+                        | VolumeUnit::Milliliter
+                        | VolumeUnit::CubicMeter
+                        | VolumeUnit::CubicCentimeter
+                )
+                | Unit::Speed(SpeedUnit::MetersPerSecond | SpeedUnit::KilometersPerHour)
+                | Unit::Area(
+                    AreaUnit::SquareMeter
+                        | AreaUnit::SquareCentimeter
+                        | AreaUnit::SquareMillimeter
+                        | AreaUnit::SquareKilometer
+                        | AreaUnit::Hectare
+                )
+        )
+    }
+
+    /// Whether the unit belongs to the imperial or US customary system.
+    /// See `is_metric` for the classified categories.
+    pub fn is_imperial(&self) -> bool {
+        matches!(
+            self,
+            Unit::Length(
+                LengthUnit::Foot
+                    | LengthUnit::Inch
+                    | LengthUnit::Yard
+                    | LengthUnit::Mile
+                    | LengthUnit::Fathom
+                    | LengthUnit::League
+            ) | Unit::Mass(
+                MassUnit::Pound
+                    | MassUnit::Ounce
+                    | MassUnit::Stone
+                    | MassUnit::ShortTon
+                    | MassUnit::LongTon
+                    | MassUnit::Grain
+            ) | Unit::Temperature(TemperatureUnit::Fahrenheit | TemperatureUnit::Rankine)
+                | Unit::Volume(
+                    VolumeUnit::USGallon
+                        | VolumeUnit::ImperialGallon
+                        | VolumeUnit::USPint
+                        | VolumeUnit::ImperialPint
+                        | VolumeUnit::USFluidOunce
+                        | VolumeUnit::ImperialFluidOunce
+                        | VolumeUnit::USCup
+                        | VolumeUnit::USTablespoon
+                        | VolumeUnit::USTeaspoon
+                        | VolumeUnit::CubicFoot
+                        | VolumeUnit::CubicInch
+                        | VolumeUnit::Barrel
+                )
+                | Unit::Speed(SpeedUnit::MilesPerHour | SpeedUnit::FeetPerSecond)
+                | Unit::Area(
+                    AreaUnit::SquareFoot
+                        | AreaUnit::SquareInch
+                        | AreaUnit::SquareYard
+                        | AreaUnit::SquareMile
+                        | AreaUnit::Acre
+                )
+        )
+    }
+
+    /// All metric units.
+    pub fn filter_metric() -> Vec<Unit> {
+        Unit::all_units()
+            .iter()
+            .copied()
+            .filter(Unit::is_metric)
+            .collect()
+    }
+
+    /// All imperial and US customary units.
+    pub fn filter_imperial() -> Vec<Unit> {
+        Unit::all_units()
+            .iter()
+            .copied()
+            .filter(Unit::is_imperial)
+            .collect()
+    }
+
+    pub fn category(&self) -> UnitCategory {
+        match self {
+            Unit::Length(_) => UnitCategory::Length,
+            Unit::Mass(_) => UnitCategory::Mass,
+            Unit::Currency(_) => UnitCategory::Currency,
+            Unit::Temperature(_) => UnitCategory::Temperature,
+            Unit::Volume(_) => UnitCategory::Volume,
+            Unit::Speed(_) => UnitCategory::Speed,
+            Unit::Pressure(_) => UnitCategory::Pressure,
+            Unit::Data(_) => UnitCategory::Data,
+            Unit::Time(_) => UnitCategory::Time,
+            Unit::Energy(_) => UnitCategory::Energy,
+            Unit::Angle(_) => UnitCategory::Angle,
+            Unit::Area(_) => UnitCategory::Area,
+            Unit::Power(_) => UnitCategory::Power,
+            Unit::FuelEfficiency(_) => UnitCategory::FuelEfficiency,
+            Unit::DataTransferRate(_) => UnitCategory::DataTransferRate,
+        }
+    }
+
+    /// Short symbol of the unit, e.g. "km".
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Unit::Length(u) => u.abbreviation(),
+            Unit::Mass(u) => u.abbreviation(),
+            Unit::Currency(u) => u.abbreviation(),
+            Unit::Temperature(u) => u.abbreviation(),
+            Unit::Volume(u) => u.abbreviation(),
+            Unit::Speed(u) => u.abbreviation(),
+            Unit::Pressure(u) => u.abbreviation(),
+            Unit::Data(u) => u.abbreviation(),
+            Unit::Time(u) => u.abbreviation(),
+            Unit::Energy(u) => u.abbreviation(),
+            Unit::Angle(u) => u.abbreviation(),
+            Unit::Area(u) => u.abbreviation(),
+            Unit::Power(u) => u.abbreviation(),
+            Unit::FuelEfficiency(u) => u.abbreviation(),
+            Unit::DataTransferRate(u) => u.abbreviation(),
+        }
+    }
+
+    /// Significant digits of `Value::display_auto_precision`, see `Unitlike::precision`.
+    pub fn precision(&self) -> u32 {
+        match self {
+            Unit::Length(u) => u.precision(),
+            Unit::Mass(u) => u.precision(),
+            Unit::Currency(u) => u.precision(),
+            Unit::Temperature(u) => u.precision(),
+            Unit::Volume(u) => u.precision(),
+            Unit::Speed(u) => u.precision(),
+            Unit::Pressure(u) => u.precision(),
+            Unit::Data(u) => u.precision(),
+            Unit::Time(u) => u.precision(),
+            Unit::Energy(u) => u.precision(),
+            Unit::Angle(u) => u.precision(),
+            Unit::Area(u) => u.precision(),
+            Unit::Power(u) => u.precision(),
+            Unit::FuelEfficiency(u) => u.precision(),
+            Unit::DataTransferRate(u) => u.precision(),
+        }
+    }
+
+    /// Whether conversions of the unit are a plain scaling, see `Convertable::is_linear`.
+    pub fn is_linear(&self) -> bool {
+        match self {
+            Unit::Length(u) => u.is_linear(),
+            Unit::Mass(u) => u.is_linear(),
+            Unit::Currency(u) => u.is_linear(),
+            Unit::Temperature(u) => u.is_linear(),
+            Unit::Volume(u) => u.is_linear(),
+            Unit::Speed(u) => u.is_linear(),
+            Unit::Pressure(u) => u.is_linear(),
+            Unit::Data(u) => u.is_linear(),
+            Unit::Time(u) => u.is_linear(),
+            Unit::Energy(u) => u.is_linear(),
+            Unit::Angle(u) => u.is_linear(),
+            Unit::Area(u) => u.is_linear(),
+            Unit::Power(u) => u.is_linear(),
+            Unit::FuelEfficiency(u) => u.is_linear(),
+            Unit::DataTransferRate(u) => u.is_linear(),
+        }
+    }
+
+    /// Long name of the unit, e.g. "kilometer".
+    pub fn full_name(&self) -> &'static str {
+        match self {
+            Unit::Length(u) => u.full_name(),
+            Unit::Mass(u) => u.full_name(),
+            Unit::Currency(u) => Unitlike::full_name(u),
+            Unit::Temperature(u) => u.full_name(),
+            Unit::Volume(u) => u.full_name(),
+            Unit::Speed(u) => u.full_name(),
+            Unit::Pressure(u) => u.full_name(),
+            Unit::Data(u) => u.full_name(),
+            Unit::Time(u) => u.full_name(),
+            Unit::Energy(u) => u.full_name(),
+            Unit::Angle(u) => u.full_name(),
+            Unit::Area(u) => u.full_name(),
+            Unit::Power(u) => u.full_name(),
+            Unit::FuelEfficiency(u) => u.full_name(),
+            Unit::DataTransferRate(u) => u.full_name(),
+        }
+    }
+
+    /// All other units this unit can be converted to.
+    pub fn compatible_units(&self) -> Vec<Unit> {
+        Unit::iter_by_category(self.category())
+            .filter(|unit| unit != self)
+            .collect()
+    }
+
+    /// All units belonging to the given category.
+    pub fn iter_by_category(category: UnitCategory) -> impl Iterator<Item = Unit> {
+        Unit::all_units()
+            .iter()
+            .copied()
+            .filter(move |unit| unit.category() == category)
+    }
+
+    /// All units, grouped by category. The list is built once and shared.
+    pub fn all_units() -> &'static [Unit] {
+        static ALL_UNITS: Lazy<Vec<Unit>> = Lazy::new(|| {
+            Unit::iter()
+                .flat_map(|unit| match unit {
+                    Unit::Length(_) => LengthUnit::iter().map(Unit::Length).collect::<Vec<Unit>>(),
+                    Unit::Mass(_) => MassUnit::iter().map(Unit::Mass).collect::<Vec<Unit>>(),
+                    Unit::Currency(_) => CurrencyUnit::iter()
+                        .map(Unit::Currency)
+                        .collect::<Vec<Unit>>(),
+                    Unit::Temperature(_) => TemperatureUnit::iter()
+                        .map(Unit::Temperature)
+                        .collect::<Vec<Unit>>(),
+                    Unit::Volume(_) => VolumeUnit::iter().map(Unit::Volume).collect::<Vec<Unit>>(),
+                    Unit::Speed(_) => SpeedUnit::iter().map(Unit::Speed).collect::<Vec<Unit>>(),
+                    Unit::Pressure(_) => PressureUnit::iter()
+                        .map(Unit::Pressure)
+                        .collect::<Vec<Unit>>(),
+                    Unit::Data(_) => DataUnit::iter().map(Unit::Data).collect::<Vec<Unit>>(),
+                    Unit::Time(_) => TimeUnit::iter().map(Unit::Time).collect::<Vec<Unit>>(),
+                    Unit::Energy(_) => EnergyUnit::iter().map(Unit::Energy).collect::<Vec<Unit>>(),
+                    Unit::Angle(_) => AngleUnit::iter().map(Unit::Angle).collect::<Vec<Unit>>(),
+                    Unit::Area(_) => AreaUnit::iter().map(Unit::Area).collect::<Vec<Unit>>(),
+                    Unit::Power(_) => PowerUnit::iter().map(Unit::Power).collect::<Vec<Unit>>(),
+                    Unit::FuelEfficiency(_) => FuelEfficiencyUnit::iter()
+                        .map(Unit::FuelEfficiency)
+                        .collect::<Vec<Unit>>(),
+                    Unit::DataTransferRate(_) => DataTransferRateUnit::iter()
+                        .map(Unit::DataTransferRate)
+                        .collect::<Vec<Unit>>(),
+                })
+                .collect()
+        });
+        &ALL_UNITS
+    }
+
+    /// All units as an owned list, prefer `all_units` to avoid the allocation.
+    pub fn get_all_units() -> Vec<Unit> {
+        Unit::all_units().to_vec()
+    }
+
+    /// Number of available units.
+    pub fn unit_count() -> usize {
+        Unit::all_units().len()
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unit::Length(u) => write!(f, "{}", u),
+            Unit::Mass(u) => write!(f, "{}", u),
+            Unit::Currency(u) => write!(f, "{}", u),
+            Unit::Temperature(u) => write!(f, "{}", u),
+            Unit::Volume(u) => write!(f, "{}", u),
+            Unit::Speed(u) => write!(f, "{}", u),
+            Unit::Pressure(u) => write!(f, "{}", u),
+            Unit::Data(u) => write!(f, "{}", u),
+            Unit::Time(u) => write!(f, "{}", u),
+            Unit::Energy(u) => write!(f, "{}", u),
+            Unit::Angle(u) => write!(f, "{}", u),
+            Unit::Area(u) => write!(f, "{}", u),
+            Unit::Power(u) => write!(f, "{}", u),
+            Unit::FuelEfficiency(u) => write!(f, "{}", u),
+            Unit::DataTransferRate(u) => write!(f, "{}", u),
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // an exact match in any category beats a case-insensitive one
+        Unit::lookup(s, true)
+            .or_else(|| Unit::lookup(s, false))
+            .ok_or_else(|| {
+                ConversionError::with_suggestion(s, suggest_unit(s))
+                    .message()
+                    .to_string()
+            })
+    }
+}
+
+/// Largest edit distance for which a unit name is suggested.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Closest unit name or abbreviation to a misspelled unit, e.g. "kilometer" for "kilometree".
+/// Case is ignored, candidates that would have to be rewritten entirely are never suggested.
+fn suggest_unit(input: &str) -> Option<&'static str> {
+    let input = input.trim().to_lowercase();
+    Unit::all_units()
+        .iter()
+        .flat_map(|unit| [unit.full_name(), unit.abbreviation()])
+        .map(|candidate| {
+            let distance = edit_distance(&input, &candidate.to_lowercase());
+            (candidate, distance)
+        })
+        .filter(|&(candidate, distance)| {
+            distance <= MAX_SUGGESTION_DISTANCE && distance < candidate.chars().count()
+        })
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(candidate, _)| candidate)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    // distances between the processed prefix of `a` and every prefix of `b`
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Units are serialized as their abbreviation, e.g. "km".
+impl Serialize for Unit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.abbreviation())
+    }
+}
+
+impl<'de> Deserialize<'de> for Unit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(de::Error::custom)
+    }
+}
+
+impl Unit {
+    /// Look up a unit of any category by name, either exactly or ignoring case.
+    fn lookup(s: &str, exact: bool) -> Option<Unit> {
+        Unit::candidates(s, exact).into_iter().next()
+    }
+
+    /// All units with the given name, at most one per category.
+    fn candidates(s: &str, exact: bool) -> Vec<Unit> {
+        fn find<U: Unitlike>(s: &str, exact: bool) -> Option<U> {
+            if exact {
+                U::find_exact(s)
+            } else {
+                U::find_case_insensitive(s)
+            }
+        }
+
+        // compound units like "m/s" are tried before their components
+        [
+            find::<SpeedUnit>(s, exact).map(Unit::Speed),
+            find::<LengthUnit>(s, exact).map(Unit::Length),
+            find::<MassUnit>(s, exact).map(Unit::Mass),
+            find::<CurrencyUnit>(s, exact).map(Unit::Currency),
+            find::<TemperatureUnit>(s, exact).map(Unit::Temperature),
+            find::<VolumeUnit>(s, exact).map(Unit::Volume),
+            find::<PressureUnit>(s, exact).map(Unit::Pressure),
+            find::<DataUnit>(s, exact).map(Unit::Data),
+            find::<TimeUnit>(s, exact).map(Unit::Time),
+            find::<EnergyUnit>(s, exact).map(Unit::Energy),
+            find::<AngleUnit>(s, exact).map(Unit::Angle),
+            find::<AreaUnit>(s, exact).map(Unit::Area),
+            find::<PowerUnit>(s, exact).map(Unit::Power),
+            find::<FuelEfficiencyUnit>(s, exact).map(Unit::FuelEfficiency),
+            find::<DataTransferRateUnit>(s, exact).map(Unit::DataTransferRate),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Parse a unit, failing instead of picking the first match when the name is ambiguous.
+    /// The error lists the matching units, it is empty when no unit matched at all.
+    pub fn parse_relaxed(s: &str) -> Result<Unit, Vec<String>> {
+        let mut candidates = Unit::candidates(s, true);
+        if candidates.is_empty() {
+            candidates = Unit::candidates(s, false);
+        }
+        Unit::single(candidates)
+    }
+
+    /// Parse a unit like `parse_relaxed`, with an error listing the candidates of an ambiguous
+    /// name or suggesting a unit for an unknown one. Used for the units of expressions.
+    pub fn parse_unambiguous(s: &str) -> Result<Unit, ConversionError> {
+        Unit::parse_relaxed(s).map_err(|matches| match matches.as_slice() {
+            [] => ConversionError::with_suggestion(s, suggest_unit(s)),
+            _ => ConversionError::ambiguous_unit(s, &matches),
+        })
+    }
+
+    /// Parse a unit by its name in the given locale, e.g. "kilomètre" in "fr".
+    /// Names the locale does not know, and unsupported locales, use the default names.
+    pub fn from_locale(locale: &str, s: &str) -> Result<Unit, ConversionError> {
+        let map = LocaleUnitMap::get(locale);
+        // an exact match beats a case-insensitive one, as in `Unit::from_str`
+        map.and_then(|map| map.find(s, true))
+            .or_else(|| Unit::lookup(s, true))
+            .or_else(|| map.and_then(|map| map.find(s, false)))
+            .or_else(|| Unit::lookup(s, false))
+            .ok_or_else(|| ConversionError::with_suggestion(s, suggest_unit(s)))
+    }
+
+    /// The only candidate, or the names of all candidates.
+    fn single(candidates: Vec<Unit>) -> Result<Unit, Vec<String>> {
+        match candidates.as_slice() {
+            [unit] => Ok(*unit),
+            _ => Err(candidates.iter().map(Unit::to_string).collect()),
+        }
+    }
+}
+
+#[allow(clippy::wrong_self_convention)]
+pub trait Convertable {
+    /// Factor from this unit to the base unit, if the conversion is a plain scaling.
+    /// Units with an offset or a non-linear conversion return None.
+    fn conversion_factor(&self) -> Option<f64> {
+        None
+    }
+    fn to_base_unit(&self, value: f64) -> ConversionResult<f64>;
+    fn from_base_unit(&self, value: f64) -> ConversionResult<f64>;
+    /// Whether converting from or to this unit is a plain scaling, i.e. converting 0 yields 0
+    /// and doubling the value doubles the result.
+    fn is_linear(&self) -> bool {
+        true
+    }
+    fn convert(value: f64, from: &Self, to: &Self) -> ConversionResult<f64> {
+        if let (Some(from), Some(to)) = (from.conversion_factor(), to.conversion_factor()) {
+            return Ok(value * from / to);
+        }
+        let base_value = from.to_base_unit(value)?;
+        to.from_base_unit(base_value)
+    }
+}
+
+/// Units converted to the base unit of their category by a constant factor.
+/// `Convertable` is implemented for them from the factor alone.
+pub trait LinearUnit {
+    /// Factor from this unit to the base unit, e.g. 1000 for kilometers.
+    fn factor(&self) -> f64;
+}
+
+impl<T: LinearUnit> Convertable for T {
+    fn conversion_factor(&self) -> Option<f64> {
+        Some(self.factor())
+    }
+    fn to_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        Ok(value * self.factor())
+    }
+    fn from_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        Ok(value / self.factor())
+    }
+}
+
+pub trait Unitlike:
+    Display
+    + PartialEq
+    + Convertable
+    + FromStr
+    + default::Default
+    + IntoEnumIterator
+    + Clone
+    + Copy
+    + 'static
+{
+    fn get_display_map() -> HashMap<(&'static str, &'static str), Self>;
+    /// The display map, built once on first use.
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), Self>;
+    /// All (long, short) name pairs, without building a new display map.
+    fn iter_names() -> impl Iterator<Item = (&'static str, &'static str)> {
+        Self::display_map_static().keys().copied()
+    }
+    /// Additional strings that parse to a unit but are never used for display.
+    fn aliases() -> Vec<(&'static str, Self)> {
+        Vec::new()
+    }
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display_map = Self::display_map_static();
+        let (long, short) = display_map.iter().find(|(_, &v)| v == *self).unwrap().0;
+        write!(f, "{} ({})", long, short)
+    }
+
+    /// Short symbol of the unit, e.g. "km".
+    fn abbreviation(&self) -> &'static str {
+        let display_map = Self::display_map_static();
+        display_map.iter().find(|(_, &v)| v == *self).unwrap().0 .1
+    }
+
+    /// Long name of the unit, e.g. "kilometer".
+    fn full_name(&self) -> &'static str {
+        let display_map = Self::display_map_static();
+        display_map.iter().find(|(_, &v)| v == *self).unwrap().0 .0
+    }
+
+    /// Significant digits `Value::display_auto_precision` shows for a value of this unit.
+    fn precision(&self) -> u32 {
+        DEFAULT_PRECISION
+    }
+
+    /// Whether parsing must not fall back to a case-insensitive match,
+    /// e.g. because "mb" and "MB" denote different units.
+    fn is_case_sensitive() -> bool {
+        false
+    }
+
+    /// Find the first unit with a name or alias satisfying the predicate.
+    fn find_unit(matches: impl Fn(&str) -> bool) -> Option<Self> {
+        Self::display_map_static()
+            .iter()
+            .find(|&((long, short), _)| matches(long) || matches(short))
+            .map(|(_, &unit)| unit)
+            .or_else(|| {
+                Self::aliases()
+                    .into_iter()
+                    .find(|(alias, _)| matches(alias))
+                    .map(|(_, unit)| unit)
+            })
+    }
+
+    fn find_exact(s: &str) -> Option<Self> {
+        Self::find_unit(|name| name == s)
+    }
+
+    fn find_case_insensitive(s: &str) -> Option<Self> {
+        if Self::is_case_sensitive() {
+            return None;
+        }
+        let s = s.to_lowercase();
+        Self::find_unit(|name| name.to_lowercase() == s)
+    }
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::find_exact(s)
+            .or_else(|| Self::find_case_insensitive(s))
+            .ok_or_else(|| {
+                ConversionError::with_suggestion(s, None)
+                    .message()
+                    .to_string()
+            })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[default]
+    Meter,
+    Centimeter,
+    Kilometer,
+    Millimeter,
+    Micrometer,
+    Nanometer,
+    Picometer,
+    Yard,
+    Foot,
+    Inch,
+    Mile,
+    NauticalMile,
+    Fathom,
+    League,
+    LightYear,
+    AstronomicalUnit,
+}
+
+impl Unitlike for LengthUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), LengthUnit> {
+        let mut m = HashMap::new();
+        m.insert(("meter", "m"), LengthUnit::Meter);
+        m.insert(("centimeter", "cm"), LengthUnit::Centimeter);
+        m.insert(("kilometer", "km"), LengthUnit::Kilometer);
+        m.insert(("yard", "yd"), LengthUnit::Yard);
+        m.insert(("foot", "ft"), LengthUnit::Foot);
+        m.insert(("inch", "in"), LengthUnit::Inch);
+        m.insert(("millimeter", "mm"), LengthUnit::Millimeter);
+        m.insert(("micrometer", "µm"), LengthUnit::Micrometer);
+        m.insert(("nanometer", "nm"), LengthUnit::Nanometer);
+        m.insert(("picometer", "pm"), LengthUnit::Picometer);
+        m.insert(("mile", "mi"), LengthUnit::Mile);
+        m.insert(("nautical mile", "nmi"), LengthUnit::NauticalMile);
+        m.insert(("fathom", "ftm"), LengthUnit::Fathom);
+        m.insert(("league", "lea"), LengthUnit::League);
+        m.insert(("light-year", "ly"), LengthUnit::LightYear);
+        m.insert(("astronomical unit", "AU"), LengthUnit::AstronomicalUnit);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), LengthUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), LengthUnit>> =
+            Lazy::new(LengthUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, LengthUnit)> {
+        vec![
+            // U+03BC GREEK SMALL LETTER MU next to the U+00B5 MICRO SIGN of the display map
+            ("μm", LengthUnit::Micrometer),
+            ("um", LengthUnit::Micrometer),
+            ("light year", LengthUnit::LightYear),
+            ("meters", LengthUnit::Meter),
+            ("centimeters", LengthUnit::Centimeter),
+            ("millimeters", LengthUnit::Millimeter),
+            ("kilometers", LengthUnit::Kilometer),
+            ("feet", LengthUnit::Foot),
+            ("inches", LengthUnit::Inch),
+            ("yards", LengthUnit::Yard),
+            ("miles", LengthUnit::Mile),
+        ]
+    }
+
+    fn precision(&self) -> u32 {
+        match self {
+            LengthUnit::Nanometer | LengthUnit::Picometer => 15,
+            _ => DEFAULT_PRECISION,
+        }
+    }
+}
+
+impl Display for LengthUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for LengthUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for LengthUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            LengthUnit::Meter => 1.0,
+            LengthUnit::Centimeter => 1.0 / 100.0,
+            LengthUnit::Kilometer => 1000.0,
+            LengthUnit::Yard => 0.9144,
+            LengthUnit::Foot => 0.3048,
+            LengthUnit::Inch => 0.0254,
+            LengthUnit::Millimeter => 1.0 / 1e3,
+            LengthUnit::Micrometer => 1.0 / 1e6,
+            LengthUnit::Nanometer => 1.0 / 1e9,
+            LengthUnit::Picometer => 1.0 / 1e12,
+            LengthUnit::Mile => 1609.344,
+            LengthUnit::NauticalMile => 1852.0,
+            LengthUnit::Fathom => 1.8288,
+            LengthUnit::League => 4828.032,
+            LengthUnit::LightYear => 9.4607e15,
+            LengthUnit::AstronomicalUnit => 1.496e11,
+        }
+    }
+}
+
+/// Units of mass.
+///
+/// The former `Ton` variant was renamed to `MetricTon`, because "ton" may also
+/// denote a US short ton or a UK long ton. "ton" still parses as a metric ton.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default, Serialize, Deserialize)]
+pub enum MassUnit {
+    #[default]
+    Kilogram,
+    Gram,
+    Milligram,
+    Microgram,
+    Nanogram,
+    MetricTon,
+    ShortTon,
+    LongTon,
+    Stone,
+    Pound,
+    Ounce,
+    Carat,
+    Grain,
+    AtomicMassUnit,
+}
+
+impl Unitlike for MassUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), MassUnit> {
+        let mut m = HashMap::new();
+        m.insert(("kilogram", "kg"), MassUnit::Kilogram);
+        m.insert(("gram", "g"), MassUnit::Gram);
+        m.insert(("tonne", "t"), MassUnit::MetricTon);
+        m.insert(("short ton", "st"), MassUnit::ShortTon);
+        m.insert(("long ton", "lt"), MassUnit::LongTon);
+        m.insert(("stone", "stone"), MassUnit::Stone);
+        m.insert(("pound", "lb"), MassUnit::Pound);
+        m.insert(("ounce", "oz"), MassUnit::Ounce);
+        m.insert(("milligram", "mg"), MassUnit::Milligram);
+        m.insert(("microgram", "µg"), MassUnit::Microgram);
+        m.insert(("nanogram", "ng"), MassUnit::Nanogram);
+        m.insert(("carat", "ct"), MassUnit::Carat);
+        m.insert(("grain", "gr"), MassUnit::Grain);
+        m.insert(("atomic mass unit", "u"), MassUnit::AtomicMassUnit);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), MassUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), MassUnit>> =
+            Lazy::new(MassUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, MassUnit)> {
+        vec![
+            ("ton", MassUnit::MetricTon),
+            ("μg", MassUnit::Microgram),
+            ("ug", MassUnit::Microgram),
+            ("Da", MassUnit::AtomicMassUnit),
+            ("dalton", MassUnit::AtomicMassUnit),
+            ("kilograms", MassUnit::Kilogram),
+            ("grams", MassUnit::Gram),
+            ("tonnes", MassUnit::MetricTon),
+            ("pounds", MassUnit::Pound),
+            ("ounces", MassUnit::Ounce),
+        ]
+    }
+}
+
+impl Display for MassUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for MassUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for MassUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            MassUnit::Kilogram => 1.0,
+            MassUnit::Gram => 1.0 / 1000.0,
+            MassUnit::MetricTon => 1000.0,
+            MassUnit::ShortTon => 907.18474,
+            MassUnit::LongTon => 1016.0469088,
+            MassUnit::Stone => 6.35029318,
+            MassUnit::Pound => 0.453592,
+            MassUnit::Ounce => 0.0283495,
+            MassUnit::Milligram => 1.0 / 1e6,
+            MassUnit::Microgram => 1.0 / 1e9,
+            MassUnit::Nanogram => 1.0 / 1e12,
+            MassUnit::Carat => 0.0002,
+            MassUnit::Grain => 6.479891e-5,
+            MassUnit::AtomicMassUnit => 1.6605390666e-27,
+        }
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, PartialEq, Clone, Copy, EnumIter, Default, Hash, Eq, Serialize, Deserialize)]
+pub enum CurrencyUnit {
+    #[default]
+    USD,
+    EUR,
+    JPY,
+    KRW,
+    GBP,
+    AUD,
+    CAD,
+    CHF,
+    CNY,
+    INR,
+    BRL,
+    MXN,
+    SEK,
+    NOK,
+    DKK,
+    PLN,
+    HUF,
+    CZK,
+    TRY,
+    ZAR,
+    SGD,
+    HKD,
+    NZD,
+    ARS,
+    IDR,
+    RUB,
+    SAR,
+}
+
+impl Unitlike for CurrencyUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), CurrencyUnit> {
+        let mut m = HashMap::new();
+        m.insert(("USD", "USD"), CurrencyUnit::USD);
+        m.insert(("EUR", "EUR"), CurrencyUnit::EUR);
+        m.insert(("JPY", "JPY"), CurrencyUnit::JPY);
+        m.insert(("KRW", "KRW"), CurrencyUnit::KRW);
+        m.insert(("GBP", "GBP"), CurrencyUnit::GBP);
+        m.insert(("AUD", "AUD"), CurrencyUnit::AUD);
+        m.insert(("CAD", "CAD"), CurrencyUnit::CAD);
+        m.insert(("CHF", "CHF"), CurrencyUnit::CHF);
+        m.insert(("CNY", "CNY"), CurrencyUnit::CNY);
+        m.insert(("INR", "INR"), CurrencyUnit::INR);
+        m.insert(("BRL", "BRL"), CurrencyUnit::BRL);
+        m.insert(("MXN", "MXN"), CurrencyUnit::MXN);
+        m.insert(("SEK", "SEK"), CurrencyUnit::SEK);
+        m.insert(("NOK", "NOK"), CurrencyUnit::NOK);
+        m.insert(("DKK", "DKK"), CurrencyUnit::DKK);
+        m.insert(("PLN", "PLN"), CurrencyUnit::PLN);
+        m.insert(("HUF", "HUF"), CurrencyUnit::HUF);
+        m.insert(("CZK", "CZK"), CurrencyUnit::CZK);
+        m.insert(("TRY", "TRY"), CurrencyUnit::TRY);
+        m.insert(("ZAR", "ZAR"), CurrencyUnit::ZAR);
+        m.insert(("SGD", "SGD"), CurrencyUnit::SGD);
+        m.insert(("HKD", "HKD"), CurrencyUnit::HKD);
+        m.insert(("NZD", "NZD"), CurrencyUnit::NZD);
+        m.insert(("ARS", "ARS"), CurrencyUnit::ARS);
+        m.insert(("IDR", "IDR"), CurrencyUnit::IDR);
+        m.insert(("RUB", "RUB"), CurrencyUnit::RUB);
+        m.insert(("SAR", "SAR"), CurrencyUnit::SAR);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), CurrencyUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), CurrencyUnit>> =
+            Lazy::new(CurrencyUnit::get_display_map);
+        &MAP
+    }
+
+    /// Symbols like "$" or "€". Symbols made of letters ("kr", "R") are left out,
+    /// they are shared by several currencies or clash with other units.
+    fn aliases() -> Vec<(&'static str, CurrencyUnit)> {
+        let symbols: Vec<&str> = CurrencyUnit::iter().map(|c| c.symbol()).collect();
+        CurrencyUnit::iter()
+            .map(|c| (c.symbol(), c))
+            .filter(|(symbol, _)| !symbol.chars().all(|c| c.is_ascii_alphabetic()))
+            .filter(|(symbol, _)| symbols.iter().filter(|s| *s == symbol).count() == 1)
+            .collect()
+    }
+
+    fn precision(&self) -> u32 {
+        6
+    }
+}
+
+/// The currency code, e.g. "USD". The alternate form `{:#}` adds the symbol: "USD ($)".
+impl Display for CurrencyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let display_map = Self::display_map_static();
+        let (long, _) = display_map.iter().find(|(_, &v)| v == *self).unwrap().0;
+        if f.alternate() && self.symbol() != *long {
+            write!(f, "{} ({})", long, self.symbol())
+        } else {
+            write!(f, "{}", long)
+        }
+    }
+}
+
+impl FromStr for CurrencyUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl Convertable for CurrencyUnit {
+    fn to_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        self.to_base_unit_with(value, ExecutionContext::global())
+    }
+
+    fn from_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        self.from_base_unit_with(value, ExecutionContext::global())
+    }
+}
+
+impl CurrencyUnit {
+    /// Symbol of the currency, e.g. "$" or "€". Currencies sharing a symbol
+    /// are told apart by a prefix, e.g. "A$" for AUD, or use their code, e.g. "CHF".
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CurrencyUnit::USD => "$",
+            CurrencyUnit::EUR => "€",
+            CurrencyUnit::JPY => "¥",
+            CurrencyUnit::KRW => "₩",
+            CurrencyUnit::GBP => "£",
+            CurrencyUnit::AUD => "A$",
+            CurrencyUnit::CAD => "CA$",
+            CurrencyUnit::CHF => "CHF",
+            CurrencyUnit::CNY => "CN¥",
+            CurrencyUnit::INR => "₹",
+            CurrencyUnit::BRL => "R$",
+            CurrencyUnit::MXN => "MX$",
+            CurrencyUnit::SEK => "kr",
+            CurrencyUnit::NOK => "kr",
+            CurrencyUnit::DKK => "kr",
+            CurrencyUnit::PLN => "zł",
+            CurrencyUnit::HUF => "Ft",
+            CurrencyUnit::CZK => "Kč",
+            CurrencyUnit::TRY => "₺",
+            CurrencyUnit::ZAR => "R",
+            CurrencyUnit::SGD => "S$",
+            CurrencyUnit::HKD => "HK$",
+            CurrencyUnit::NZD => "NZ$",
+            CurrencyUnit::ARS => "AR$",
+            CurrencyUnit::IDR => "Rp",
+            CurrencyUnit::RUB => "₽",
+            CurrencyUnit::SAR => "SR",
+        }
+    }
+
+    /// Name of the currency, e.g. "US Dollar" for USD.
+    /// `Unit::full_name` keeps the ISO 4217 code, which is what the parser understands.
+    pub fn full_name(&self) -> &'static str {
+        match self {
+            CurrencyUnit::USD => "US Dollar",
+            CurrencyUnit::EUR => "Euro",
+            CurrencyUnit::JPY => "Japanese Yen",
+            CurrencyUnit::KRW => "South Korean Won",
+            CurrencyUnit::GBP => "British Pound Sterling",
+            CurrencyUnit::AUD => "Australian Dollar",
+            CurrencyUnit::CAD => "Canadian Dollar",
+            CurrencyUnit::CHF => "Swiss Franc",
+            CurrencyUnit::CNY => "Chinese Yuan",
+            CurrencyUnit::INR => "Indian Rupee",
+            CurrencyUnit::BRL => "Brazilian Real",
+            CurrencyUnit::MXN => "Mexican Peso",
+            CurrencyUnit::SEK => "Swedish Krona",
+            CurrencyUnit::NOK => "Norwegian Krone",
+            CurrencyUnit::DKK => "Danish Krone",
+            CurrencyUnit::PLN => "Polish Zloty",
+            CurrencyUnit::HUF => "Hungarian Forint",
+            CurrencyUnit::CZK => "Czech Koruna",
+            CurrencyUnit::TRY => "Turkish Lira",
+            CurrencyUnit::ZAR => "South African Rand",
+            CurrencyUnit::SGD => "Singapore Dollar",
+            CurrencyUnit::HKD => "Hong Kong Dollar",
+            CurrencyUnit::NZD => "New Zealand Dollar",
+            CurrencyUnit::ARS => "Argentine Peso",
+            CurrencyUnit::IDR => "Indonesian Rupiah",
+            CurrencyUnit::RUB => "Russian Ruble",
+            CurrencyUnit::SAR => "Saudi Riyal",
+        }
+    }
+
+    /// Convert the value to USD with the exchange rates of the given context.
+    pub fn to_base_unit_with(&self, value: f64, ctx: &ExecutionContext) -> ConversionResult<f64> {
+        ctx.with_cache(|cache| cache.get_base_rate(*self))
+            .map(|rate| value / rate)
+            .map_err(|e| {
+                ConversionError::with_source(ConversionErrorKind::NetworkError, e.to_string(), e)
+            })
+    }
+
+    /// Variant of `from_base_unit` with the exchange rates of the given context.
+    pub fn from_base_unit_with(&self, value: f64, ctx: &ExecutionContext) -> ConversionResult<f64> {
+        ctx.with_cache(|cache| cache.get_base_rate(*self))
+            .map(|rate| value * rate)
+            .map_err(|e| {
+                ConversionError::with_source(ConversionErrorKind::NetworkError, e.to_string(), e)
+            })
+    }
+
+    /// Variant of `convert` with the exchange rates of the given context.
+    pub fn convert_with(
+        value: f64,
+        from: &CurrencyUnit,
+        to: &CurrencyUnit,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<f64> {
+        let base_value = from.to_base_unit_with(value, ctx)?;
+        let unit_value = to.to_base_unit_with(1.0, ctx)?;
+        Ok(base_value / unit_value)
+    }
+}
+
+#[cfg(feature = "async")]
+impl CurrencyUnit {
+    /// Async variant of `to_base_unit_with`, which does not block the thread while rates are requested.
+    pub async fn to_base_unit_async(
+        &self,
+        value: f64,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<f64> {
+        ctx.cache
+            .lock()
+            .await
+            .get_base_rate_async(*self)
+            .await
+            .map(|rate| value / rate)
+            .map_err(|e| {
+                ConversionError::with_source(ConversionErrorKind::NetworkError, e.to_string(), e)
+            })
+    }
+
+    /// Async variant of `convert_with`.
+    pub async fn convert_async(
+        value: f64,
+        from: &CurrencyUnit,
+        to: &CurrencyUnit,
+        ctx: &ExecutionContext,
+    ) -> ConversionResult<f64> {
+        let base_value = from.to_base_unit_async(value, ctx).await?;
+        let unit_value = to.to_base_unit_async(1.0, ctx).await?;
+        Ok(base_value / unit_value)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum TemperatureUnit {
+    #[default]
+    Kelvin,
+    Celsius,
+    Fahrenheit,
+    Rankine,
+}
+
+impl Unitlike for TemperatureUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), TemperatureUnit> {
+        let mut m = HashMap::new();
+        m.insert(("kelvin", "K"), TemperatureUnit::Kelvin);
+        m.insert(("celsius", "°C"), TemperatureUnit::Celsius);
+        m.insert(("fahrenheit", "°F"), TemperatureUnit::Fahrenheit);
+        m.insert(("rankine", "°R"), TemperatureUnit::Rankine);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), TemperatureUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), TemperatureUnit>> =
+            Lazy::new(TemperatureUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, TemperatureUnit)> {
+        vec![
+            // the degree sign is often left out
+            ("C", TemperatureUnit::Celsius),
+            ("F", TemperatureUnit::Fahrenheit),
+            ("R", TemperatureUnit::Rankine),
+            ("°K", TemperatureUnit::Kelvin),
+            // notation of scientific software
+            ("degC", TemperatureUnit::Celsius),
+            ("degF", TemperatureUnit::Fahrenheit),
+            ("degK", TemperatureUnit::Kelvin),
+        ]
+    }
+}
+
+impl Display for TemperatureUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+/// Temperature scales are offset from each other, so both directions are
+/// implemented explicitly instead of relying on a single scaling factor.
+impl Convertable for TemperatureUnit {
+    /// Kelvin and Rankine both start at absolute zero, the other scales are offset.
+    fn is_linear(&self) -> bool {
+        matches!(self, TemperatureUnit::Kelvin | TemperatureUnit::Rankine)
+    }
+
+    fn to_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        let kelvin = match self {
+            TemperatureUnit::Kelvin => value,
+            TemperatureUnit::Celsius => value + 273.15,
+            TemperatureUnit::Fahrenheit => (value + 459.67) * 5.0 / 9.0,
+            TemperatureUnit::Rankine => value * 5.0 / 9.0,
+        };
+        if kelvin < 0.0 {
+            return Err(ConversionError::new(
+                ConversionErrorKind::NegativeTemperature,
+                format!("{} {} is below absolute zero", value, self),
+            ));
+        }
+        Ok(kelvin)
+    }
+
+    fn from_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        let val = match self {
+            TemperatureUnit::Kelvin => value,
+            TemperatureUnit::Celsius => value - 273.15,
+            TemperatureUnit::Fahrenheit => value * 9.0 / 5.0 - 459.67,
+            TemperatureUnit::Rankine => value * 9.0 / 5.0,
+        };
+        Ok(val)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum VolumeUnit {
+    #[default]
+    Liter,
+    Milliliter,
+    CubicMeter,
+    CubicCentimeter,
+    USGallon,
+    ImperialGallon,
+    USPint,
+    ImperialPint,
+    USFluidOunce,
+    ImperialFluidOunce,
+    USCup,
+    USTablespoon,
+    USTeaspoon,
+    CubicFoot,
+    CubicInch,
+    Barrel,
+}
+
+impl Unitlike for VolumeUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), VolumeUnit> {
+        let mut m = HashMap::new();
+        m.insert(("liter", "L"), VolumeUnit::Liter);
+        m.insert(("milliliter", "mL"), VolumeUnit::Milliliter);
+        m.insert(("cubic meter", "m3"), VolumeUnit::CubicMeter);
+        m.insert(("cubic centimeter", "cm3"), VolumeUnit::CubicCentimeter);
+        m.insert(("US gallon", "gal"), VolumeUnit::USGallon);
+        m.insert(("imperial gallon", "imp gal"), VolumeUnit::ImperialGallon);
+        m.insert(("US pint", "pt"), VolumeUnit::USPint);
+        m.insert(("imperial pint", "imp pt"), VolumeUnit::ImperialPint);
+        m.insert(("US fluid ounce", "fl oz"), VolumeUnit::USFluidOunce);
+        m.insert(
+            ("imperial fluid ounce", "imp fl oz"),
+            VolumeUnit::ImperialFluidOunce,
+        );
+        m.insert(("US cup", "cup"), VolumeUnit::USCup);
+        m.insert(("US tablespoon", "tbsp"), VolumeUnit::USTablespoon);
+        m.insert(("US teaspoon", "tsp"), VolumeUnit::USTeaspoon);
+        m.insert(("cubic foot", "ft3"), VolumeUnit::CubicFoot);
+        m.insert(("cubic inch", "in3"), VolumeUnit::CubicInch);
+        m.insert(("barrel", "bbl"), VolumeUnit::Barrel);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), VolumeUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), VolumeUnit>> =
+            Lazy::new(VolumeUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, VolumeUnit)> {
+        vec![
+            ("m³", VolumeUnit::CubicMeter),
+            ("cm³", VolumeUnit::CubicCentimeter),
+            ("ft³", VolumeUnit::CubicFoot),
+            ("in³", VolumeUnit::CubicInch),
+        ]
+    }
+}
+
+impl Display for VolumeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for VolumeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for VolumeUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            VolumeUnit::Liter => 1.0,
+            VolumeUnit::Milliliter => 1.0 / 1000.0,
+            VolumeUnit::CubicMeter => 1000.0,
+            VolumeUnit::CubicCentimeter => 1.0 / 1000.0,
+            VolumeUnit::USGallon => 3.785411784,
+            VolumeUnit::ImperialGallon => 4.54609,
+            VolumeUnit::USPint => 0.473176473,
+            VolumeUnit::ImperialPint => 0.56826125,
+            VolumeUnit::USFluidOunce => 0.0295735295625,
+            VolumeUnit::ImperialFluidOunce => 0.0284130625,
+            VolumeUnit::USCup => 0.2365882365,
+            VolumeUnit::USTablespoon => 0.01478676478125,
+            VolumeUnit::USTeaspoon => 0.00492892159375,
+            VolumeUnit::CubicFoot => 28.316846592,
+            VolumeUnit::CubicInch => 0.016387064,
+            VolumeUnit::Barrel => 158.987294928,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum SpeedUnit {
+    #[default]
+    MetersPerSecond,
+    KilometersPerHour,
+    MilesPerHour,
+    Knot,
+    FeetPerSecond,
+    Mach,
+}
+
+impl Unitlike for SpeedUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), SpeedUnit> {
+        let mut m = HashMap::new();
+        m.insert(("meters per second", "m/s"), SpeedUnit::MetersPerSecond);
+        m.insert(
+            ("kilometers per hour", "km/h"),
+            SpeedUnit::KilometersPerHour,
+        );
+        m.insert(("miles per hour", "mph"), SpeedUnit::MilesPerHour);
+        m.insert(("knot", "kn"), SpeedUnit::Knot);
+        m.insert(("feet per second", "ft/s"), SpeedUnit::FeetPerSecond);
+        m.insert(("mach", "Mach"), SpeedUnit::Mach);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), SpeedUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), SpeedUnit>> =
+            Lazy::new(SpeedUnit::get_display_map);
+        &MAP
+    }
+}
+
+impl Display for SpeedUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for SpeedUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for SpeedUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            SpeedUnit::MetersPerSecond => 1.0,
+            SpeedUnit::KilometersPerHour => 1.0 / 3.6,
+            SpeedUnit::MilesPerHour => 0.44704,
+            SpeedUnit::Knot => 1852.0 / 3600.0,
+            SpeedUnit::FeetPerSecond => 0.3048,
+            // speed of sound in dry air at sea level and 15 °C
+            SpeedUnit::Mach => 340.29,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum PressureUnit {
+    #[default]
+    Pascal,
+    Kilopascal,
+    Megapascal,
+    Bar,
+    Millibar,
+    Psi,
+    StandardAtmosphere,
+    TechnicalAtmosphere,
+    Torr,
+    MillimeterOfMercury,
+}
+
+impl Unitlike for PressureUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), PressureUnit> {
+        let mut m = HashMap::new();
+        m.insert(("pascal", "Pa"), PressureUnit::Pascal);
+        m.insert(("kilopascal", "kPa"), PressureUnit::Kilopascal);
+        m.insert(("megapascal", "MPa"), PressureUnit::Megapascal);
+        m.insert(("bar", "bar"), PressureUnit::Bar);
+        m.insert(("millibar", "mbar"), PressureUnit::Millibar);
+        m.insert(("pound per square inch", "psi"), PressureUnit::Psi);
+        m.insert(
+            ("standard atmosphere", "atm"),
+            PressureUnit::StandardAtmosphere,
+        );
+        m.insert(
+            ("technical atmosphere", "at"),
+            PressureUnit::TechnicalAtmosphere,
+        );
+        m.insert(("torr", "Torr"), PressureUnit::Torr);
+        m.insert(
+            ("millimeter of mercury", "mmHg"),
+            PressureUnit::MillimeterOfMercury,
+        );
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), PressureUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), PressureUnit>> =
+            Lazy::new(PressureUnit::get_display_map);
+        &MAP
+    }
+}
+
+impl Display for PressureUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for PressureUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for PressureUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            PressureUnit::Pascal => 1.0,
+            PressureUnit::Kilopascal => 1e3,
+            PressureUnit::Megapascal => 1e6,
+            PressureUnit::Bar => 1e5,
+            PressureUnit::Millibar => 100.0,
+            PressureUnit::Psi => 6894.757293168,
+            PressureUnit::StandardAtmosphere => 101325.0,
+            PressureUnit::TechnicalAtmosphere => 98066.5,
+            // defined as 1/760 of a standard atmosphere
+            PressureUnit::Torr => 101325.0 / 760.0,
+            PressureUnit::MillimeterOfMercury => 133.322387415,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum DataUnit {
+    #[default]
+    Bit,
+    Byte,
+    Kilobyte,
+    Megabyte,
+    Gigabyte,
+    Terabyte,
+    Petabyte,
+    Kibibyte,
+    Mebibyte,
+    Gibibyte,
+    Tebibyte,
+    Pebibyte,
+}
+
+impl Unitlike for DataUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), DataUnit> {
+        let mut m = HashMap::new();
+        m.insert(("bit", "b"), DataUnit::Bit);
+        m.insert(("byte", "B"), DataUnit::Byte);
+        m.insert(("kilobyte", "kB"), DataUnit::Kilobyte);
+        m.insert(("megabyte", "MB"), DataUnit::Megabyte);
+        m.insert(("gigabyte", "GB"), DataUnit::Gigabyte);
+        m.insert(("terabyte", "TB"), DataUnit::Terabyte);
+        m.insert(("petabyte", "PB"), DataUnit::Petabyte);
+        m.insert(("kibibyte", "KiB"), DataUnit::Kibibyte);
+        m.insert(("mebibyte", "MiB"), DataUnit::Mebibyte);
+        m.insert(("gibibyte", "GiB"), DataUnit::Gibibyte);
+        m.insert(("tebibyte", "TiB"), DataUnit::Tebibyte);
+        m.insert(("pebibyte", "PiB"), DataUnit::Pebibyte);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), DataUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), DataUnit>> =
+            Lazy::new(DataUnit::get_display_map);
+        &MAP
+    }
+
+    fn is_case_sensitive() -> bool {
+        true
+    }
+}
+
+impl Display for DataUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for DataUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for DataUnit {
+    fn factor(&self) -> f64 {
+        let bytes = match self {
+            DataUnit::Bit => 1.0 / 8.0,
+            DataUnit::Byte => 1.0,
+            DataUnit::Kilobyte => 1e3,
+            DataUnit::Megabyte => 1e6,
+            DataUnit::Gigabyte => 1e9,
+            DataUnit::Terabyte => 1e12,
+            DataUnit::Petabyte => 1e15,
+            DataUnit::Kibibyte => 1024.0,
+            DataUnit::Mebibyte => 1024f64.powi(2),
+            DataUnit::Gibibyte => 1024f64.powi(3),
+            DataUnit::Tebibyte => 1024f64.powi(4),
+            DataUnit::Pebibyte => 1024f64.powi(5),
+        };
+        bytes * 8.0
+    }
+}
+
+/// Units of data transfer rate with bits per second as base.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum DataTransferRateUnit {
+    #[default]
+    BitsPerSecond,
+    KilobitsPerSecond,
+    MegabitsPerSecond,
+    GigabitsPerSecond,
+    TerabitsPerSecond,
+    BytesPerSecond,
+    KilobytesPerSecond,
+    MegabytesPerSecond,
+    GigabytesPerSecond,
+}
+
+impl Unitlike for DataTransferRateUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), DataTransferRateUnit> {
+        let mut m = HashMap::new();
+        m.insert(
+            ("bit per second", "bps"),
+            DataTransferRateUnit::BitsPerSecond,
+        );
+        m.insert(
+            ("kilobit per second", "kbps"),
+            DataTransferRateUnit::KilobitsPerSecond,
+        );
+        m.insert(
+            ("megabit per second", "Mbps"),
+            DataTransferRateUnit::MegabitsPerSecond,
+        );
+        m.insert(
+            ("gigabit per second", "Gbps"),
+            DataTransferRateUnit::GigabitsPerSecond,
+        );
+        m.insert(
+            ("terabit per second", "Tbps"),
+            DataTransferRateUnit::TerabitsPerSecond,
+        );
+        m.insert(
+            ("byte per second", "Bps"),
+            DataTransferRateUnit::BytesPerSecond,
+        );
+        m.insert(
+            ("kilobyte per second", "kBps"),
+            DataTransferRateUnit::KilobytesPerSecond,
+        );
+        m.insert(
+            ("megabyte per second", "MBps"),
+            DataTransferRateUnit::MegabytesPerSecond,
+        );
+        m.insert(
+            ("gigabyte per second", "GBps"),
+            DataTransferRateUnit::GigabytesPerSecond,
+        );
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), DataTransferRateUnit>
+    {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), DataTransferRateUnit>> =
+            Lazy::new(DataTransferRateUnit::get_display_map);
+        &MAP
+    }
+
+    fn is_case_sensitive() -> bool {
+        // "Mbps" and "MBps" differ by a factor of 8
+        true
+    }
+}
+
+impl Display for DataTransferRateUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for DataTransferRateUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for DataTransferRateUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            DataTransferRateUnit::BitsPerSecond => 1.0,
+            DataTransferRateUnit::KilobitsPerSecond => 1e3,
+            DataTransferRateUnit::MegabitsPerSecond => 1e6,
+            DataTransferRateUnit::GigabitsPerSecond => 1e9,
+            DataTransferRateUnit::TerabitsPerSecond => 1e12,
+            DataTransferRateUnit::BytesPerSecond => 8.0,
+            DataTransferRateUnit::KilobytesPerSecond => 8e3,
+            DataTransferRateUnit::MegabytesPerSecond => 8e6,
+            DataTransferRateUnit::GigabytesPerSecond => 8e9,
+        }
+    }
+}
+
+/// Units of time with the second as base.
+///
+/// Months and years have no fixed length, so `Month` (30.4375 days) and
+/// `Year` (365.25 days) are averages over the Gregorian calendar and any
+/// conversion involving them is approximate.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum TimeUnit {
+    Nanosecond,
+    Microsecond,
+    Millisecond,
+    #[default]
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl Unitlike for TimeUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), TimeUnit> {
+        let mut m = HashMap::new();
+        m.insert(("nanosecond", "ns"), TimeUnit::Nanosecond);
+        m.insert(("microsecond", "µs"), TimeUnit::Microsecond);
+        m.insert(("millisecond", "ms"), TimeUnit::Millisecond);
+        m.insert(("second", "s"), TimeUnit::Second);
+        m.insert(("minute", "min"), TimeUnit::Minute);
+        m.insert(("hour", "hr"), TimeUnit::Hour);
+        m.insert(("day", "d"), TimeUnit::Day);
+        m.insert(("week", "wk"), TimeUnit::Week);
+        m.insert(("month", "mo"), TimeUnit::Month);
+        m.insert(("year", "yr"), TimeUnit::Year);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), TimeUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), TimeUnit>> =
+            Lazy::new(TimeUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, TimeUnit)> {
+        vec![
+            ("us", TimeUnit::Microsecond),
+            ("h", TimeUnit::Hour),
+            ("y", TimeUnit::Year),
+        ]
+    }
+}
+
+impl Display for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for TimeUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for TimeUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            TimeUnit::Nanosecond => 1.0 / 1e9,
+            TimeUnit::Microsecond => 1.0 / 1e6,
+            TimeUnit::Millisecond => 1.0 / 1e3,
+            TimeUnit::Second => 1.0,
+            TimeUnit::Minute => 60.0,
+            TimeUnit::Hour => 3600.0,
+            TimeUnit::Day => 86400.0,
+            TimeUnit::Week => 604800.0,
+            TimeUnit::Month => 2629800.0,
+            TimeUnit::Year => 31557600.0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum EnergyUnit {
+    #[default]
+    Joule,
+    Kilojoule,
+    Megajoule,
+    Calorie,
+    Kilocalorie,
+    WattHour,
+    KilowattHour,
+    MegawattHour,
+    Btu,
+    FootPound,
+    ElectronVolt,
+}
+
+impl Unitlike for EnergyUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), EnergyUnit> {
+        let mut m = HashMap::new();
+        m.insert(("joule", "J"), EnergyUnit::Joule);
+        m.insert(("kilojoule", "kJ"), EnergyUnit::Kilojoule);
+        m.insert(("megajoule", "MJ"), EnergyUnit::Megajoule);
+        m.insert(("calorie", "cal"), EnergyUnit::Calorie);
+        m.insert(("kilocalorie", "kcal"), EnergyUnit::Kilocalorie);
+        m.insert(("watt hour", "Wh"), EnergyUnit::WattHour);
+        m.insert(("kilowatt hour", "kWh"), EnergyUnit::KilowattHour);
+        m.insert(("megawatt hour", "MWh"), EnergyUnit::MegawattHour);
+        m.insert(("british thermal unit", "BTU"), EnergyUnit::Btu);
+        m.insert(("foot-pound", "ft·lbf"), EnergyUnit::FootPound);
+        m.insert(("electronvolt", "eV"), EnergyUnit::ElectronVolt);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), EnergyUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), EnergyUnit>> =
+            Lazy::new(EnergyUnit::get_display_map);
+        &MAP
+    }
+
+    fn is_case_sensitive() -> bool {
+        true
+    }
+
+    fn aliases() -> Vec<(&'static str, EnergyUnit)> {
+        // "Cal" is the food calorie found on nutrition labels
+        vec![
+            ("Cal", EnergyUnit::Kilocalorie),
+            ("ft-lbf", EnergyUnit::FootPound),
+        ]
+    }
+}
+
+impl Display for EnergyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for EnergyUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for EnergyUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            EnergyUnit::Joule => 1.0,
+            EnergyUnit::Kilojoule => 1e3,
+            EnergyUnit::Megajoule => 1e6,
+            // thermochemical calorie
+            EnergyUnit::Calorie => 4.184,
+            EnergyUnit::Kilocalorie => 4184.0,
+            EnergyUnit::WattHour => 3600.0,
+            EnergyUnit::KilowattHour => 3.6e6,
+            EnergyUnit::MegawattHour => 3.6e9,
+            // International Table BTU
+            EnergyUnit::Btu => 1055.05585262,
+            EnergyUnit::FootPound => 1.3558179483314004,
+            EnergyUnit::ElectronVolt => 1.602176634e-19,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum AngleUnit {
+    #[default]
+    Radian,
+    Degree,
+    Gradian,
+    Turn,
+    ArcMinute,
+    ArcSecond,
+    Milliradian,
+}
+
+impl Unitlike for AngleUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), AngleUnit> {
+        let mut m = HashMap::new();
+        m.insert(("radian", "rad"), AngleUnit::Radian);
+        m.insert(("degree", "deg"), AngleUnit::Degree);
+        m.insert(("gradian", "grad"), AngleUnit::Gradian);
+        m.insert(("turn", "turn"), AngleUnit::Turn);
+        m.insert(("arcminute", "arcmin"), AngleUnit::ArcMinute);
+        m.insert(("arcsecond", "arcsec"), AngleUnit::ArcSecond);
+        m.insert(("milliradian", "mrad"), AngleUnit::Milliradian);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), AngleUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), AngleUnit>> =
+            Lazy::new(AngleUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, AngleUnit)> {
+        vec![
+            ("°", AngleUnit::Degree),
+            ("gon", AngleUnit::Gradian),
+            ("'", AngleUnit::ArcMinute),
+            ("\"", AngleUnit::ArcSecond),
+        ]
+    }
+}
+
+impl Display for AngleUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for AngleUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for AngleUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            AngleUnit::Radian => 1.0,
+            AngleUnit::Degree => PI / 180.0,
+            AngleUnit::Gradian => PI / 200.0,
+            AngleUnit::Turn => 2.0 * PI,
+            AngleUnit::ArcMinute => PI / 10800.0,
+            AngleUnit::ArcSecond => PI / 648000.0,
+            AngleUnit::Milliradian => 1.0 / 1000.0,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum AreaUnit {
+    #[default]
+    SquareMeter,
+    SquareCentimeter,
+    SquareMillimeter,
+    SquareKilometer,
+    SquareFoot,
+    SquareInch,
+    SquareYard,
+    SquareMile,
+    Acre,
+    Hectare,
+}
+
+impl Unitlike for AreaUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), AreaUnit> {
+        let mut m = HashMap::new();
+        m.insert(("square meter", "m2"), AreaUnit::SquareMeter);
+        m.insert(("square centimeter", "cm2"), AreaUnit::SquareCentimeter);
+        m.insert(("square millimeter", "mm2"), AreaUnit::SquareMillimeter);
+        m.insert(("square kilometer", "km2"), AreaUnit::SquareKilometer);
+        m.insert(("square foot", "ft2"), AreaUnit::SquareFoot);
+        m.insert(("square inch", "in2"), AreaUnit::SquareInch);
+        m.insert(("square yard", "yd2"), AreaUnit::SquareYard);
+        m.insert(("square mile", "mi2"), AreaUnit::SquareMile);
+        m.insert(("acre", "ac"), AreaUnit::Acre);
+        m.insert(("hectare", "ha"), AreaUnit::Hectare);
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), AreaUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), AreaUnit>> =
+            Lazy::new(AreaUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, AreaUnit)> {
+        vec![
+            ("m²", AreaUnit::SquareMeter),
+            ("cm²", AreaUnit::SquareCentimeter),
+            ("mm²", AreaUnit::SquareMillimeter),
+            ("km²", AreaUnit::SquareKilometer),
+            ("ft²", AreaUnit::SquareFoot),
+            ("in²", AreaUnit::SquareInch),
+            ("yd²", AreaUnit::SquareYard),
+            ("mi²", AreaUnit::SquareMile),
+        ]
+    }
+}
+
+impl Display for AreaUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for AreaUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for AreaUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            AreaUnit::SquareMeter => 1.0,
+            AreaUnit::SquareCentimeter => 1.0 / 1e4,
+            AreaUnit::SquareMillimeter => 1.0 / 1e6,
+            AreaUnit::SquareKilometer => 1e6,
+            AreaUnit::SquareFoot => 0.09290304,
+            AreaUnit::SquareInch => 0.00064516,
+            AreaUnit::SquareYard => 0.83612736,
+            AreaUnit::SquareMile => 2589988.110336,
+            AreaUnit::Acre => 4046.8564224,
+            AreaUnit::Hectare => 1e4,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum PowerUnit {
+    #[default]
+    Watt,
+    Kilowatt,
+    Megawatt,
+    Gigawatt,
+    MetricHorsepower,
+    MechanicalHorsepower,
+    ElectricalHorsepower,
+    BtuPerHour,
+    FtLbfPerSecond,
+    KgfMPerSecond,
+}
+
+impl Unitlike for PowerUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), PowerUnit> {
+        let mut m = HashMap::new();
+        m.insert(("watt", "W"), PowerUnit::Watt);
+        m.insert(("kilowatt", "kW"), PowerUnit::Kilowatt);
+        m.insert(("megawatt", "MW"), PowerUnit::Megawatt);
+        m.insert(("gigawatt", "GW"), PowerUnit::Gigawatt);
+        m.insert(("metric horsepower", "PS"), PowerUnit::MetricHorsepower);
+        m.insert(
+            ("mechanical horsepower", "hp"),
+            PowerUnit::MechanicalHorsepower,
+        );
+        m.insert(
+            ("electrical horsepower", "hp(E)"),
+            PowerUnit::ElectricalHorsepower,
+        );
+        m.insert(("BTU per hour", "BTU/h"), PowerUnit::BtuPerHour);
+        m.insert(
+            ("foot-pound-force per second", "ft·lbf/s"),
+            PowerUnit::FtLbfPerSecond,
+        );
+        m.insert(
+            ("kilogram-force meter per second", "kgf·m/s"),
+            PowerUnit::KgfMPerSecond,
+        );
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), PowerUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), PowerUnit>> =
+            Lazy::new(PowerUnit::get_display_map);
+        &MAP
+    }
+
+    fn aliases() -> Vec<(&'static str, PowerUnit)> {
+        vec![("cv", PowerUnit::MetricHorsepower)]
+    }
+}
+
+impl Display for PowerUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for PowerUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl LinearUnit for PowerUnit {
+    fn factor(&self) -> f64 {
+        match self {
+            PowerUnit::Watt => 1.0,
+            PowerUnit::Kilowatt => 1e3,
+            PowerUnit::Megawatt => 1e6,
+            PowerUnit::Gigawatt => 1e9,
+            // 75 kgf·m/s
+            PowerUnit::MetricHorsepower => 735.49875,
+            // 550 ft·lbf/s
+            PowerUnit::MechanicalHorsepower => 745.6998715822702,
+            PowerUnit::ElectricalHorsepower => 746.0,
+            PowerUnit::BtuPerHour => 1055.05585262 / 3600.0,
+            PowerUnit::FtLbfPerSecond => 1.3558179483314004,
+            PowerUnit::KgfMPerSecond => 9.80665,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, EnumIter, Default)]
+pub enum FuelEfficiencyUnit {
+    #[default]
+    KilometersPerLiter,
+    MilesPerGallonUS,
+    MilesPerGallonImperial,
+    LitersPer100km,
+    MilesPerLiter,
+}
+
+impl Unitlike for FuelEfficiencyUnit {
+    fn get_display_map() -> HashMap<(&'static str, &'static str), FuelEfficiencyUnit> {
+        let mut m = HashMap::new();
+        m.insert(
+            ("kilometers per liter", "km/L"),
+            FuelEfficiencyUnit::KilometersPerLiter,
+        );
+        m.insert(
+            ("miles per US gallon", "mpg"),
+            FuelEfficiencyUnit::MilesPerGallonUS,
+        );
+        m.insert(
+            ("miles per imperial gallon", "mpg (imp)"),
+            FuelEfficiencyUnit::MilesPerGallonImperial,
+        );
+        m.insert(
+            ("liters per 100 kilometers", "L/100km"),
+            FuelEfficiencyUnit::LitersPer100km,
+        );
+        m.insert(
+            ("miles per liter", "mi/L"),
+            FuelEfficiencyUnit::MilesPerLiter,
+        );
+        m
+    }
+
+    fn display_map_static() -> &'static HashMap<(&'static str, &'static str), FuelEfficiencyUnit> {
+        static MAP: Lazy<HashMap<(&'static str, &'static str), FuelEfficiencyUnit>> =
+            Lazy::new(FuelEfficiencyUnit::get_display_map);
+        &MAP
+    }
+}
+
+impl Display for FuelEfficiencyUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Unitlike::fmt(self, f)
+    }
+}
+
+impl FromStr for FuelEfficiencyUnit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Unitlike::from_str(s)
+    }
+}
+
+impl FuelEfficiencyUnit {
+    /// Invert a value between distance per volume and volume per distance.
+    fn invert(value: f64) -> ConversionResult<f64> {
+        if value == 0.0 {
+            return Err(ConversionError::new(
+                ConversionErrorKind::DivisionByZero,
+                "Division by zero",
+            ));
+        }
+        Ok(100.0 / value)
+    }
+
+    /// Kilometers per liter of one unit, None for liters per 100 km, which is inverted instead.
+    fn km_per_liter(&self) -> Option<f64> {
+        match self {
+            FuelEfficiencyUnit::KilometersPerLiter => Some(1.0),
+            FuelEfficiencyUnit::MilesPerGallonUS => Some(1.609344 / 3.785411784),
+            FuelEfficiencyUnit::MilesPerGallonImperial => Some(1.609344 / 4.54609),
+            FuelEfficiencyUnit::LitersPer100km => None,
+            FuelEfficiencyUnit::MilesPerLiter => Some(1.609344),
+        }
+    }
+}
+
+/// Liters per 100 km is inversely proportional to the other units, so it is
+/// inverted on the way to and from the base unit (km/L).
+impl Convertable for FuelEfficiencyUnit {
+    /// Converting to or from liters per 100 km has no single factor, so none of the units has one.
+    fn is_linear(&self) -> bool {
+        false
+    }
+
+    fn to_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        match self.km_per_liter() {
+            Some(factor) => Ok(value * factor),
+            None => Self::invert(value),
+        }
+    }
+
+    fn from_base_unit(&self, value: f64) -> ConversionResult<f64> {
+        match self.km_per_liter() {
+            Some(factor) => Ok(value / factor),
+            None => Self::invert(value),
+        }
+    }
+}
+
+// test eq of value
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_value_eq() {
+        let v1 = Value {
+            value: Some(1.0),
+            unit: Unit::Length(LengthUnit::Meter),
+        };
+        let v2 = Value {
+            value: Some(1.0),
+            unit: Unit::Length(LengthUnit::Meter),
+        };
+
+        let v3 = Value {
+            value: Some(1.0),
+            unit: Unit::Length(LengthUnit::Kilometer),
+        };
+
+        let v4 = Value {
+            value: Some(2.0),
+            unit: Unit::Length(LengthUnit::Meter),
+        };
+
+        assert_eq!(v1, v2);
+        assert_ne!(v1, v3);
+        assert_ne!(v1, v4);
+    }
+
+    #[test]
+    fn test_unit_eq() {
+        let u1 = Unit::Length(LengthUnit::Meter);
+        let u2 = Unit::Length(LengthUnit::Meter);
+        assert_eq!(u1, u2);
+
+        let u3 = Unit::Length(LengthUnit::Kilometer);
+        assert_ne!(u1, u3);
+        assert_eq!(u1.category(), u3.category());
+
+        let u4 = Unit::Mass(MassUnit::Kilogram);
+        assert_ne!(u1, u4);
+    }
+
+    #[test]
+    fn test_length_conversion() {
+        let v = Value::new(1.0, Unit::Length(LengthUnit::Meter));
+        let v2 = v.convert_to(&Unit::Length(LengthUnit::Kilometer)).unwrap();
+        assert_eq!(v2.unit, Unit::Length(LengthUnit::Kilometer));
+        assert!(v2.approx_eq(&Value::new(0.001, v2.unit)).unwrap());
+    }
+
+    #[test]
+    fn test_length_conversion_edge_case() {
+        let v = Value::new(0.0, Unit::Length(LengthUnit::Meter));
+        let v2 = v.convert_to(&Unit::Length(LengthUnit::Kilometer)).unwrap();
+        assert_eq!(v2.unit, Unit::Length(LengthUnit::Kilometer));
+        assert!(v2.approx_eq(&Value::new(0.0, v2.unit)).unwrap());
+    }
+
+    #[test]
+    fn test_mass_conversion() {
+        let v = Value::new(1.0, Unit::Mass(MassUnit::Kilogram));
+        let v2 = v.convert_to(&Unit::Mass(MassUnit::Gram)).unwrap();
+        assert_eq!(v2.unit, Unit::Mass(MassUnit::Gram));
+        assert!(v2.approx_eq(&Value::new(1000.0, v2.unit)).unwrap());
+    }
+
+    #[test]
+    fn test_currency_conversion() {
+        let v = Value::new(1.0, Unit::Currency(CurrencyUnit::USD));
+        let v2 = v.convert_to(&Unit::Currency(CurrencyUnit::EUR));
+        assert!((v2.unwrap().value.unwrap() - 0.9).abs() < 1e-9);
+    }
+
+    fn convert_temperature(value: f64, from: TemperatureUnit, to: TemperatureUnit) -> f64 {
+        Value::new(value, Unit::Temperature(from))
+            .convert_to(&Unit::Temperature(to))
+            .unwrap()
+            .value
+            .unwrap()
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("meter", "meter"), 0);
+        assert_eq!(edit_distance("metere", "meter"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("µm", "um"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest_unit() {
+        assert_eq!(suggest_unit("kilometree"), Some("kilometer"));
+        assert_eq!(suggest_unit("kilometre"), Some("kilometer"));
+        assert_eq!(suggest_unit("poundd"), Some("pound"));
+        assert_eq!(suggest_unit("metere"), Some("meter"));
+        assert_eq!(suggest_unit("xqzvbnwjk"), None);
+    }
+
+    #[test]
+    fn test_invalid_unit_suggestion() {
+        assert_eq!(
+            "poundd".parse::<Unit>(),
+            Err("Invalid unit: 'poundd'. Did you mean 'pound'?".to_string())
+        );
+        assert_eq!(
+            "xqzvbnwjk".parse::<Unit>(),
+            Err("Invalid unit: 'xqzvbnwjk'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_to_si() {
+        assert_eq!(
+            Value::new(1.0, Unit::Length(LengthUnit::Kilometer)).to_si(),
+            Ok(Value::new(1000.0, Unit::Length(LengthUnit::Meter)))
+        );
+        let kg = Value::new(1.0, Unit::Mass(MassUnit::Pound))
+            .to_si()
+            .unwrap();
+        assert_eq!(kg.unit, Unit::Mass(MassUnit::Kilogram));
+        assert!((kg.value.unwrap() - 0.453592).abs() < 1e-9);
+        assert_eq!(
+            Value::new(0.0, Unit::Temperature(TemperatureUnit::Celsius)).to_si(),
+            Ok(Value::new(
+                273.15,
+                Unit::Temperature(TemperatureUnit::Kelvin)
+            ))
+        );
+
+        for unit in Unit::get_all_units() {
+            assert_eq!(unit.si_base().category(), unit.category());
+        }
+    }
+
+    #[test]
+    fn test_mul_div() {
+        let m = Unit::Length(LengthUnit::Meter);
+        assert_eq!(Value::new(5.0, m) * 2.0, Value::new(10.0, m));
+        assert_eq!(&Value::new(5.0, m) * 2.0, Value::new(10.0, m));
+        assert_eq!(Value::new(10.0, m) / 2.0, Value::new(5.0, m));
+        assert_eq!(&Value::new(10.0, m) / 4.0, Value::new(2.5, m));
+    }
+
+    #[test]
+    fn test_div_by_zero() {
+        let m = Unit::Length(LengthUnit::Meter);
+        let value = Value::new(10.0, m) / 0.0;
+        assert_eq!(
+            value,
+            Value {
+                value: None,
+                unit: m
+            }
+        );
+        assert!(value
+            .convert_to(&Unit::Length(LengthUnit::Kilometer))
+            .is_err());
+        assert_eq!((value * 2.0).value, None);
+    }
+
+    #[test]
+    fn test_scale() {
+        let km = Unit::Length(LengthUnit::Kilometer);
+        assert_eq!(Value::new(5.0, km).scale(2.0), Value::new(10.0, km));
+        assert_eq!(Value::new(5.0, km).scale(-0.5), Value::new(-2.5, km));
+
+        let none = Value {
+            value: None,
+            unit: km,
+        };
+        assert_eq!(none.scale(2.0), none);
+    }
+
+    #[test]
+    fn test_negate() {
+        let km = Unit::Length(LengthUnit::Kilometer);
+        assert_eq!(Value::new(5.0, km).negate(), Ok(Value::new(-5.0, km)));
+
+        let celsius = Unit::Temperature(TemperatureUnit::Celsius);
+        assert_eq!(
+            Value::new(5.0, celsius).negate(),
+            Ok(Value::new(-5.0, celsius))
+        );
+
+        let kelvin = Unit::Temperature(TemperatureUnit::Kelvin);
+        assert!(Value::new(5.0, kelvin).negate().is_err());
+        assert!(Value::new(5.0, Unit::Data(DataUnit::Byte))
+            .negate()
+            .is_err());
+    }
+
+    #[test]
+    fn test_conversion_factor() {
+        assert_eq!(LengthUnit::Kilometer.conversion_factor(), Some(1000.0));
+        assert_eq!(DataUnit::Bit.conversion_factor(), Some(1.0));
+        assert_eq!(DataUnit::Byte.conversion_factor(), Some(8.0));
+        assert_eq!(TemperatureUnit::Celsius.conversion_factor(), None);
+        assert_eq!(FuelEfficiencyUnit::LitersPer100km.conversion_factor(), None);
+        assert_eq!(CurrencyUnit::EUR.conversion_factor(), None);
+
+        assert_eq!(
+            LengthUnit::convert(2.0, &LengthUnit::Kilometer, &LengthUnit::Meter),
+            Ok(2000.0)
+        );
+        assert_eq!(LengthUnit::Kilometer.to_base_unit(2.0), Ok(2000.0));
+        assert_eq!(LengthUnit::Kilometer.from_base_unit(2000.0), Ok(2.0));
+    }
+
+    #[test]
+    fn test_temperature_conversion() {
+        let kelvin = convert_temperature(0.0, TemperatureUnit::Celsius, TemperatureUnit::Kelvin);
+        assert!((kelvin - 273.15).abs() < 1e-9);
+
+        let fahrenheit =
+            convert_temperature(-40.0, TemperatureUnit::Celsius, TemperatureUnit::Fahrenheit);
+        assert!((fahrenheit + 40.0).abs() < 1e-9);
+
+        let rankine =
+            convert_temperature(0.0, TemperatureUnit::Fahrenheit, TemperatureUnit::Rankine);
+        assert!((rankine - 459.67).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_temperature_below_absolute_zero() {
+        let v = Value::new(-1.0, Unit::Temperature(TemperatureUnit::Kelvin));
+        assert!(v
+            .convert_to(&Unit::Temperature(TemperatureUnit::Celsius))
+            .is_err());
+
+        let v = Value::new(-274.0, Unit::Temperature(TemperatureUnit::Celsius));
+        assert!(v
+            .convert_to(&Unit::Temperature(TemperatureUnit::Kelvin))
+            .is_err());
+
+        let v = Value::new(-273.15, Unit::Temperature(TemperatureUnit::Celsius));
+        assert!(v
+            .convert_to(&Unit::Temperature(TemperatureUnit::Kelvin))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_linear_unit() {
+        assert_eq!(LengthUnit::Kilometer.factor(), 1000.0);
+        assert_eq!(LengthUnit::Kilometer.conversion_factor(), Some(1000.0));
+        assert_eq!(LengthUnit::Kilometer.from_base_unit(500.0).unwrap(), 0.5);
+        assert_eq!(TemperatureUnit::Celsius.conversion_factor(), None);
+        assert_eq!(FuelEfficiencyUnit::MilesPerLiter.conversion_factor(), None);
+        assert_eq!(
+            FuelEfficiencyUnit::MilesPerLiter
+                .from_base_unit(1.609344)
+                .unwrap(),
+            1.0
+        );
+    }
+
+    #[test]
+    fn test_is_linear() {
+        assert!(LengthUnit::Meter.is_linear());
+        assert!(CurrencyUnit::EUR.is_linear());
+        assert!(!TemperatureUnit::Celsius.is_linear());
+        assert!(!TemperatureUnit::Fahrenheit.is_linear());
+        assert!(TemperatureUnit::Kelvin.is_linear());
+        assert!(TemperatureUnit::Rankine.is_linear());
+        assert!(!FuelEfficiencyUnit::LitersPer100km.is_linear());
+        assert!(!Unit::Temperature(TemperatureUnit::Celsius).is_linear());
+        assert!(Unit::Length(LengthUnit::Meter).is_linear());
+    }
+
+    #[test]
+    fn test_temperature_roundtrip() {
+        for from in TemperatureUnit::iter() {
+            for to in TemperatureUnit::iter() {
+                // sweep from absolute zero up to 10000 K
+                for step in 0..=1000 {
+                    let kelvin = step as f64 * 10.0;
+                    let start = TemperatureUnit::from_base_unit(&from, kelvin).unwrap();
+                    let there = convert_temperature(start, from, to);
+                    let back = convert_temperature(there, to, from);
+                    assert!((back - start).abs() < 1e-9 * start.abs().max(1.0));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_temperature_unit_from_str() {
+        for s in ["celsius", "Celsius", "C", "c", "°C", "degC"] {
+            assert_eq!(s.parse::<TemperatureUnit>(), Ok(TemperatureUnit::Celsius));
+            assert_eq!(
+                s.parse::<Unit>(),
+                Ok(Unit::Temperature(TemperatureUnit::Celsius))
+            );
+        }
+        for s in ["fahrenheit", "F", "°F", "degF"] {
+            assert_eq!(
+                s.parse::<TemperatureUnit>(),
+                Ok(TemperatureUnit::Fahrenheit)
+            );
+        }
+        for s in ["kelvin", "K", "°K", "degK"] {
+            assert_eq!(s.parse::<TemperatureUnit>(), Ok(TemperatureUnit::Kelvin));
+        }
+        for s in ["rankine", "R"] {
+            assert_eq!(s.parse::<TemperatureUnit>(), Ok(TemperatureUnit::Rankine));
+        }
+        assert_eq!(
+            "°C".parse::<Unit>(),
+            Ok(Unit::Temperature(TemperatureUnit::Celsius))
+        );
+    }
+
+    #[test]
+    fn test_volume_conversion() {
+        let v = Value::new(1.0, Unit::Volume(VolumeUnit::USGallon));
+        let v2 = v
+            .convert_to(&Unit::Volume(VolumeUnit::USFluidOunce))
+            .unwrap();
+        assert!((v2.value.unwrap() - 128.0).abs() < 1e-9);
+
+        let v = Value::new(1.0, Unit::Volume(VolumeUnit::USGallon));
+        let v2 = v
+            .convert_to(&Unit::Volume(VolumeUnit::ImperialGallon))
+            .unwrap();
+        assert!((v2.value.unwrap() - 1.0).abs() > 0.1);
+
+        // one US quart is two US pints
+        let v = Value::new(1.0, Unit::Volume(VolumeUnit::Liter));
+        let v2 = v.convert_to(&Unit::Volume(VolumeUnit::USPint)).unwrap();
+        assert!((v2.value.unwrap() - 2.0).abs() > 0.1);
+    }
+
+    #[test]
+    fn test_volume_unit_from_str() {
+        assert_eq!("L".parse::<Unit>(), Ok(Unit::Volume(VolumeUnit::Liter)));
+        assert_eq!("fl oz".parse::<VolumeUnit>(), Ok(VolumeUnit::USFluidOunce));
+        assert_eq!("m³".parse::<VolumeUnit>(), Ok(VolumeUnit::CubicMeter));
+        assert_eq!("bbl".parse::<VolumeUnit>(), Ok(VolumeUnit::Barrel));
+    }
+
+    #[test]
+    fn test_speed_conversion() {
+        let v = Value::new(1.0, Unit::Speed(SpeedUnit::Knot));
+        let v2 = v.convert_to(&Unit::Speed(SpeedUnit::MilesPerHour)).unwrap();
+        assert!((v2.value.unwrap() - 1.15078).abs() < 1e-5);
+
+        let v = Value::new(1.0, Unit::Speed(SpeedUnit::Mach));
+        let v2 = v
+            .convert_to(&Unit::Speed(SpeedUnit::KilometersPerHour))
+            .unwrap();
+        assert!((v2.value.unwrap() - 1225.044).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_speed_unit_from_str() {
+        assert_eq!(
+            "m/s".parse::<Unit>(),
+            Ok(Unit::Speed(SpeedUnit::MetersPerSecond))
+        );
+        assert_eq!(
+            "km/h".parse::<Unit>(),
+            Ok(Unit::Speed(SpeedUnit::KilometersPerHour))
+        );
+        assert_eq!("m".parse::<Unit>(), Ok(Unit::Length(LengthUnit::Meter)));
+    }
+
+    #[test]
+    fn test_pressure_conversion() {
+        let v = Value::new(1.0, Unit::Pressure(PressureUnit::StandardAtmosphere));
+        let v2 = v.convert_to(&Unit::Pressure(PressureUnit::Pascal)).unwrap();
+        assert_eq!(v2.unit, Unit::Pressure(PressureUnit::Pascal));
+        assert!(v2.approx_eq(&Value::new(101325.0, v2.unit)).unwrap());
+
+        let v = Value::new(123456.0, Unit::Pressure(PressureUnit::Pascal));
+        let psi = v.convert_to(&Unit::Pressure(PressureUnit::Psi)).unwrap();
+        let back = psi
+            .convert_to(&Unit::Pressure(PressureUnit::Pascal))
+            .unwrap();
+        assert!((back.value.unwrap() - 123456.0).abs() / 123456.0 < 1e-6);
+
+        let v = Value::new(1.0, Unit::Pressure(PressureUnit::Torr));
+        let v2 = v
+            .convert_to(&Unit::Pressure(PressureUnit::MillimeterOfMercury))
+            .unwrap();
+        assert_ne!(v2.value.unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_data_conversion() {
+        let gib = Value::new(1.0, Unit::Data(DataUnit::Gibibyte))
+            .convert_to(&Unit::Data(DataUnit::Byte))
+            .unwrap();
+        assert!(gib.approx_eq(&Value::new(1073741824.0, gib.unit)).unwrap());
+
+        let gb = Value::new(1.0, Unit::Data(DataUnit::Gigabyte))
+            .convert_to(&Unit::Data(DataUnit::Byte))
+            .unwrap();
+        assert!(gb.approx_eq(&Value::new(1000000000.0, gb.unit)).unwrap());
+
+        assert_ne!(gib.value, gb.value);
+    }
+
+    #[test]
+    fn test_data_unit_from_str_is_case_sensitive() {
+        assert_eq!("MB".parse::<DataUnit>(), Ok(DataUnit::Megabyte));
+        assert_eq!("b".parse::<DataUnit>(), Ok(DataUnit::Bit));
+        assert_eq!("B".parse::<DataUnit>(), Ok(DataUnit::Byte));
+        assert!("mb".parse::<DataUnit>().is_err());
+    }
+
+    #[test]
+    fn test_data_transfer_rate_conversion() {
+        let rate = Value::new(
+            1.0,
+            Unit::DataTransferRate(DataTransferRateUnit::GigabitsPerSecond),
+        )
+        .convert_to(&Unit::DataTransferRate(
+            DataTransferRateUnit::MegabytesPerSecond,
+        ))
+        .unwrap();
+        assert!(rate.approx_eq(&Value::new(125.0, rate.unit)).unwrap());
+        assert_eq!(
+            "Mbps".parse::<Unit>(),
+            Ok(Unit::DataTransferRate(
+                DataTransferRateUnit::MegabitsPerSecond
+            ))
+        );
+        assert_eq!(
+            "MBps".parse::<Unit>(),
+            Ok(Unit::DataTransferRate(
+                DataTransferRateUnit::MegabytesPerSecond
+            ))
+        );
+        assert!("mbps".parse::<Unit>().is_err());
+        assert!("mb".parse::<Unit>().is_err());
     }
 
     #[test]
-    fn test_mass_conversion() {
-        let v = Value::new(1.0, Unit::Mass(MassUnit::Kilogram));
-        let v2 = v.convert_to(&Unit::Mass(MassUnit::Gram)).unwrap();
-        assert_eq!(v2, Value::new(1000.0, Unit::Mass(MassUnit::Gram)));
+    fn test_time_conversion() {
+        let v = Value::new(1.0, Unit::Time(TimeUnit::Week));
+        let v2 = v.convert_to(&Unit::Time(TimeUnit::Second)).unwrap();
+        assert!(v2.approx_eq(&Value::new(604800.0, v2.unit)).unwrap());
+
+        let v = Value::new(3.0, Unit::Time(TimeUnit::Day));
+        let ns = v.convert_to(&Unit::Time(TimeUnit::Nanosecond)).unwrap();
+        assert!((ns.value.unwrap() - 2.592e14).abs() / 2.592e14 < 1e-12);
+        let back = ns.convert_to(&Unit::Time(TimeUnit::Day)).unwrap();
+        assert!((back.value.unwrap() - 3.0).abs() < 1e-12);
     }
 
     #[test]
-    fn test_currency_conversion() {
-        let v = Value::new(1.0, Unit::Currency(CurrencyUnit::USD));
-        let v2 = v.convert_to(&Unit::Currency(CurrencyUnit::EUR));
-        assert!(v2.is_ok());
+    fn test_time_approximate_display() {
+        let v = Value::new(1.0, Unit::Time(TimeUnit::Year));
+        assert!(v.to_string().ends_with("(approximate)"));
+
+        let v = Value::new(1.0, Unit::Time(TimeUnit::Week));
+        assert!(!v.to_string().ends_with("(approximate)"));
+    }
+
+    #[test]
+    fn test_time_unit_from_str() {
+        assert_eq!("us".parse::<TimeUnit>(), Ok(TimeUnit::Microsecond));
+        assert_eq!("µs".parse::<TimeUnit>(), Ok(TimeUnit::Microsecond));
+        assert_eq!("h".parse::<TimeUnit>(), Ok(TimeUnit::Hour));
+        assert_eq!("y".parse::<Unit>(), Ok(Unit::Time(TimeUnit::Year)));
+    }
+
+    #[test]
+    fn test_energy_conversion() {
+        let v = Value::new(1.0, Unit::Energy(EnergyUnit::KilowattHour));
+        let v2 = v.convert_to(&Unit::Energy(EnergyUnit::Joule)).unwrap();
+        assert!(v2.approx_eq(&Value::new(3600000.0, v2.unit)).unwrap());
+
+        let v = Value::new(1.0, Unit::Energy(EnergyUnit::Btu));
+        let v2 = v.convert_to(&Unit::Energy(EnergyUnit::Joule)).unwrap();
+        assert!((v2.value.unwrap() - 1055.056).abs() < 1e-3);
+
+        let v = Value::new(250.0, Unit::Energy(EnergyUnit::Kilocalorie));
+        let v2 = v.convert_to(&Unit::Energy(EnergyUnit::Kilojoule)).unwrap();
+        assert!((v2.value.unwrap() - 1046.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_energy_unit_from_str_is_case_sensitive() {
+        assert_eq!("cal".parse::<EnergyUnit>(), Ok(EnergyUnit::Calorie));
+        assert_eq!("Cal".parse::<EnergyUnit>(), Ok(EnergyUnit::Kilocalorie));
+        assert_eq!("kcal".parse::<EnergyUnit>(), Ok(EnergyUnit::Kilocalorie));
+    }
+
+    #[test]
+    fn test_angle_conversion() {
+        let v = Value::new(360.0, Unit::Angle(AngleUnit::Degree));
+        let v2 = v.convert_to(&Unit::Angle(AngleUnit::Turn)).unwrap();
+        assert!((v2.value.unwrap() - 1.0).abs() < 1e-12);
+
+        let v = Value::new(1.0, Unit::Angle(AngleUnit::Radian));
+        let v2 = v.convert_to(&Unit::Angle(AngleUnit::Degree)).unwrap();
+        assert!((v2.value.unwrap() - 57.2958).abs() < 1e-4);
+
+        let v = Value::new(123.456789, Unit::Angle(AngleUnit::Degree));
+        let rad = v.convert_to(&Unit::Angle(AngleUnit::Radian)).unwrap();
+        let back = rad.convert_to(&Unit::Angle(AngleUnit::Degree)).unwrap();
+        assert!((back.value.unwrap() - 123.456789).abs() / 123.456789 < 1e-10);
+    }
+
+    #[test]
+    fn test_angle_unit_from_str() {
+        assert_eq!("°".parse::<AngleUnit>(), Ok(AngleUnit::Degree));
+        assert_eq!("'".parse::<AngleUnit>(), Ok(AngleUnit::ArcMinute));
+        assert_eq!("\"".parse::<AngleUnit>(), Ok(AngleUnit::ArcSecond));
+        assert_eq!("gon".parse::<Unit>(), Ok(Unit::Angle(AngleUnit::Gradian)));
+    }
+
+    #[test]
+    fn test_area_conversion() {
+        let v = Value::new(1.0, Unit::Area(AreaUnit::Hectare));
+        let v2 = v.convert_to(&Unit::Area(AreaUnit::SquareMeter)).unwrap();
+        assert!(v2.approx_eq(&Value::new(10000.0, v2.unit)).unwrap());
+
+        let v = Value::new(1.0, Unit::Area(AreaUnit::Acre));
+        let v2 = v.convert_to(&Unit::Area(AreaUnit::SquareMeter)).unwrap();
+        assert!((v2.value.unwrap() - 4046.856).abs() < 1e-3);
+
+        let v = Value::new(1.0, Unit::Area(AreaUnit::SquareMile));
+        let v2 = v.convert_to(&Unit::Area(AreaUnit::Acre)).unwrap();
+        assert!((v2.value.unwrap() - 640.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_area_unit_from_str() {
+        assert_eq!("m2".parse::<AreaUnit>(), Ok(AreaUnit::SquareMeter));
+        assert_eq!("m²".parse::<AreaUnit>(), Ok(AreaUnit::SquareMeter));
+        assert_eq!(
+            "km²".parse::<Unit>(),
+            Ok(Unit::Area(AreaUnit::SquareKilometer))
+        );
+    }
+
+    #[test]
+    fn test_power_conversion() {
+        let v = Value::new(1.0, Unit::Power(PowerUnit::Kilowatt));
+        let v2 = v.convert_to(&Unit::Power(PowerUnit::Watt)).unwrap();
+        assert!(v2.approx_eq(&Value::new(1000.0, v2.unit)).unwrap());
+
+        let watts = |unit| {
+            Value::new(1.0, Unit::Power(unit))
+                .convert_to(&Unit::Power(PowerUnit::Watt))
+                .unwrap()
+                .value
+                .unwrap()
+        };
+        let metric = watts(PowerUnit::MetricHorsepower);
+        let mechanical = watts(PowerUnit::MechanicalHorsepower);
+        let electrical = watts(PowerUnit::ElectricalHorsepower);
+        assert_ne!(metric, mechanical);
+        assert_ne!(metric, electrical);
+        assert_ne!(mechanical, electrical);
+        assert_eq!(electrical, 746.0);
+    }
+
+    #[test]
+    fn test_power_unit_from_str() {
+        assert_eq!("PS".parse::<PowerUnit>(), Ok(PowerUnit::MetricHorsepower));
+        assert_eq!("cv".parse::<PowerUnit>(), Ok(PowerUnit::MetricHorsepower));
+        assert_eq!(
+            "hp".parse::<PowerUnit>(),
+            Ok(PowerUnit::MechanicalHorsepower)
+        );
+        assert_eq!(
+            "hp(E)".parse::<Unit>(),
+            Ok(Unit::Power(PowerUnit::ElectricalHorsepower))
+        );
+    }
+
+    #[test]
+    fn test_fuel_efficiency_conversion() {
+        let v = Value::new(
+            30.0,
+            Unit::FuelEfficiency(FuelEfficiencyUnit::MilesPerGallonUS),
+        );
+        let l_per_100km = v
+            .convert_to(&Unit::FuelEfficiency(FuelEfficiencyUnit::LitersPer100km))
+            .unwrap();
+        assert!((l_per_100km.value.unwrap() - 7.84).abs() < 1e-2);
+
+        let back = l_per_100km
+            .convert_to(&Unit::FuelEfficiency(FuelEfficiencyUnit::MilesPerGallonUS))
+            .unwrap();
+        assert!((back.value.unwrap() - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fuel_efficiency_division_by_zero() {
+        let v = Value::new(
+            0.0,
+            Unit::FuelEfficiency(FuelEfficiencyUnit::MilesPerGallonUS),
+        );
+        assert!(v
+            .convert_to(&Unit::FuelEfficiency(FuelEfficiencyUnit::LitersPer100km))
+            .is_err());
+
+        let v = Value::new(
+            0.0,
+            Unit::FuelEfficiency(FuelEfficiencyUnit::LitersPer100km),
+        );
+        assert!(v
+            .convert_to(&Unit::FuelEfficiency(
+                FuelEfficiencyUnit::KilometersPerLiter
+            ))
+            .is_err());
+    }
+
+    #[test]
+    fn test_negative_value_conversion() {
+        let v = Value::new(-40.0, Unit::Temperature(TemperatureUnit::Celsius));
+        let v2 = v
+            .convert_to(&Unit::Temperature(TemperatureUnit::Fahrenheit))
+            .unwrap();
+        assert!((v2.value.unwrap() + 40.0).abs() < 1e-9);
+
+        let v = Value::new(-50.0, Unit::Length(LengthUnit::Meter));
+        let v2 = v.convert_to(&Unit::Length(LengthUnit::Kilometer)).unwrap();
+        assert!(v2.approx_eq(&Value::new(-0.05, v2.unit)).unwrap());
+
+        // -273.16 °C lies just below absolute zero
+        let v = Value::new(-273.16, Unit::Temperature(TemperatureUnit::Celsius));
+        assert!(v
+            .convert_to(&Unit::Temperature(TemperatureUnit::Kelvin))
+            .is_err());
+    }
+
+    #[test]
+    fn test_unit_from_str_case_insensitive() {
+        assert_eq!(
+            "KM".parse::<Unit>(),
+            Ok(Unit::Length(LengthUnit::Kilometer))
+        );
+        assert_eq!(
+            "Kilometer".parse::<Unit>(),
+            Ok(Unit::Length(LengthUnit::Kilometer))
+        );
+        assert_eq!("FOOT".parse::<Unit>(), Ok(Unit::Length(LengthUnit::Foot)));
+        assert_eq!("usd".parse::<Unit>(), Ok(Unit::Currency(CurrencyUnit::USD)));
+        assert_eq!("usd".parse::<CurrencyUnit>(), Ok(CurrencyUnit::USD));
+        assert!("xyz".parse::<Unit>().is_err());
+
+        // exact matches take precedence over case-insensitive ones
+        assert_eq!("b".parse::<Unit>(), Ok(Unit::Data(DataUnit::Bit)));
+        assert_eq!("B".parse::<Unit>(), Ok(Unit::Data(DataUnit::Byte)));
+        assert_eq!(
+            "Cal".parse::<Unit>(),
+            Ok(Unit::Energy(EnergyUnit::Kilocalorie))
+        );
+        assert!("CAL".parse::<Unit>().is_err());
+    }
+
+    #[test]
+    fn test_iter_names() {
+        let names: Vec<_> = LengthUnit::iter_names().collect();
+        assert_eq!(names.len(), LengthUnit::iter().count());
+        assert!(names.contains(&("kilometer", "km")));
+        assert!(DataTransferRateUnit::iter_names().any(|(_, short)| short == "Mbps"));
+    }
+
+    #[test]
+    fn test_parse_relaxed() {
+        // "t" is only used by the tonne
+        assert_eq!(
+            Unit::parse_relaxed("t"),
+            Ok(Unit::Mass(MassUnit::MetricTon))
+        );
+        assert_eq!(
+            Unit::parse_relaxed("KM"),
+            Ok(Unit::Length(LengthUnit::Kilometer))
+        );
+        assert_eq!(Unit::parse_relaxed("xyz"), Err(vec![]));
+        assert_eq!(
+            Unit::parse_unambiguous("kilometree").unwrap_err().message(),
+            "Invalid unit: 'kilometree'. Did you mean 'kilometer'?"
+        );
+
+        // no name or alias of any unit is ambiguous today, not even ignoring case
+        for unit in Unit::all_units() {
+            for name in [unit.full_name(), unit.abbreviation()] {
+                for name in [name.to_string(), name.to_lowercase(), name.to_uppercase()] {
+                    if let Err(matches) = Unit::parse_relaxed(&name) {
+                        assert!(matches.is_empty(), "'{}' is ambiguous: {:?}", name, matches);
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            ConversionError::ambiguous_unit("t", &["tonne (t)".into(), "time (t)".into()])
+                .message(),
+            "Ambiguous unit 't'. Did you mean: tonne (t), time (t)?"
+        );
+    }
+
+    #[test]
+    fn test_from_compound_str() {
+        let height = Value::from_compound_str("5 ft 11 in").unwrap();
+        assert_eq!(height.unit, Unit::Length(LengthUnit::Foot));
+        assert!((height.value.unwrap() - (5.0 + 11.0 / 12.0)).abs() < 1e-9);
+
+        let length = Value::from_compound_str("2 m 30 cm").unwrap();
+        assert_eq!(length.unit, Unit::Length(LengthUnit::Meter));
+        assert!((length.value.unwrap() - 2.3).abs() < 1e-9);
+
+        assert!(Value::from_compound_str("5 ft 11").is_err());
+        assert!(Value::from_compound_str("5 ft 11 kg").is_err());
+        assert!(Value::from_compound_str("").is_err());
+    }
+
+    #[test]
+    fn test_numeral_systems() {
+        let byte = |value| Value::new(value, Unit::Data(DataUnit::Byte));
+        assert_eq!(
+            Value::from_str_with_base("400 B", NumeralSystem::Hexadecimal),
+            Ok(byte(1024.0))
+        );
+        assert_eq!(
+            Value::from_str_with_base("0x400 B", NumeralSystem::Hexadecimal),
+            Ok(byte(1024.0))
+        );
+        assert_eq!("0x400 B".parse::<Value>(), Ok(byte(1024.0)));
+        assert_eq!("0b101 B".parse::<Value>(), Ok(byte(5.0)));
+        assert_eq!("0o17 B".parse::<Value>(), Ok(byte(15.0)));
+        assert_eq!("-0xFF B".parse::<Value>(), Ok(byte(-255.0)));
+        assert!("0x1.5 B".parse::<Value>().is_err());
+        assert!(Value::from_str_with_base("102 B", NumeralSystem::Binary).is_err());
+
+        assert_eq!(
+            byte(1024.0)
+                .with_precision(0)
+                .in_base(NumeralSystem::Hexadecimal)
+                .to_string(),
+            "0x400 B"
+        );
+        let options = FormatOptions {
+            base: NumeralSystem::Binary,
+            ..Default::default()
+        };
+        assert_eq!(byte(5.0).format(&options), "0b101 B");
+        // fractions stay decimal
+        assert_eq!(byte(0.5).format(&options), "0.5 B");
+    }
+
+    #[test]
+    fn test_unit_precision() {
+        assert_eq!(Unit::Currency(CurrencyUnit::EUR).precision(), 6);
+        assert_eq!(Unit::Length(LengthUnit::Nanometer).precision(), 15);
+        assert_eq!(Unit::Length(LengthUnit::Kilometer).precision(), 10);
+
+        let nanometer = Value::new(1.0, Unit::Length(LengthUnit::Nanometer));
+        let kilometer = Value::new(1.0, Unit::Length(LengthUnit::Kilometer));
+        let decimals = |s: String| s.split(['.', ' ']).nth(1).unwrap().len();
+        assert_eq!(kilometer.display_auto_precision(), "1.000000000 km");
+        assert!(
+            decimals(nanometer.display_auto_precision())
+                > decimals(kilometer.display_auto_precision())
+        );
+        assert_eq!(
+            Value::new(1234.5, Unit::Currency(CurrencyUnit::USD)).display_auto_precision(),
+            "1234.50 USD"
+        );
+
+        // the default display is exact, the precision only applies to `display_auto_precision`
+        let usd = Value::new(1234567.89, Unit::Currency(CurrencyUnit::USD));
+        assert_eq!(usd.to_string(), "1234567.89 USD");
+        assert_eq!(usd.display_auto_precision(), "1234568 USD");
+    }
+
+    #[test]
+    fn test_approx_eq() {
+        let yard = Value::new(1.0, Unit::Length(LengthUnit::Yard));
+        let roundtrip = yard
+            .convert_to(&Unit::Length(LengthUnit::Meter))
+            .unwrap()
+            .convert_to(&Unit::Length(LengthUnit::Yard))
+            .unwrap();
+        assert!(yard.is_approximately_equal(&roundtrip, 1e-10).unwrap());
+        assert!(yard.approx_eq(&roundtrip).unwrap());
+
+        let meters = Value::new(0.9144, Unit::Length(LengthUnit::Meter));
+        assert!(yard.approx_eq(&meters).unwrap());
+        assert!(!yard
+            .approx_eq(&Value::new(0.9145, Unit::Length(LengthUnit::Meter)))
+            .unwrap());
+        assert!(yard
+            .is_approximately_equal(&Value::new(0.9145, Unit::Length(LengthUnit::Meter)), 1e-3)
+            .unwrap());
+
+        let zero = Value::new(0.0, Unit::Temperature(TemperatureUnit::Celsius));
+        assert!(zero
+            .approx_eq(&Value::new(
+                273.15,
+                Unit::Temperature(TemperatureUnit::Kelvin)
+            ))
+            .unwrap());
+
+        assert_eq!(
+            yard.approx_eq(&Value::new(1.0, Unit::Mass(MassUnit::Kilogram)))
+                .unwrap_err()
+                .kind(),
+            ConversionErrorKind::UnsupportedConversion
+        );
+    }
+
+    #[test]
+    fn test_value_conversions() {
+        let km = Unit::Length(LengthUnit::Kilometer);
+        let v: ConversionResult<Value> = (100.0_f64, "km").try_into();
+        assert_eq!(v, Ok(Value::new(100.0, km)));
+        let v: ConversionResult<Value> = (1.0_f64, "xyz").try_into();
+        assert_eq!(v.unwrap_err().kind(), ConversionErrorKind::ParseError);
+        assert_eq!(Value::try_from("5 km"), Ok(Value::new(5.0, km)));
+        assert!(Value::try_from("5").is_err());
+
+        assert_eq!(f64::from(Value::new(5.0, km)), 5.0);
+        let missing = Value {
+            value: None,
+            unit: km,
+        };
+        assert!(f64::from(missing).is_nan());
+        let (value, unit) = Value::new(5.0, km).into();
+        assert_eq!((value, unit), (5.0, km));
+    }
+
+    #[test]
+    fn test_value_as_hash_map_key() {
+        let meter = Value::new(1.0, Unit::Length(LengthUnit::Meter));
+        let kilometer = Value::new(1.0, Unit::Length(LengthUnit::Kilometer));
+        let mut labels = HashMap::new();
+        labels.insert(meter, "meter");
+        labels.insert(kilometer, "kilometer");
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels.get(&meter), Some(&"meter"));
+        assert_eq!(labels.get(&kilometer), Some(&"kilometer"));
+        assert_eq!(labels.get(&Value::new(1000.0, meter.unit())), None);
+
+        let zero = Value::new(0.0, meter.unit());
+        labels.insert(zero, "zero");
+        assert_eq!(labels.get(&Value::new(-0.0, meter.unit())), Some(&"zero"));
+    }
+
+    #[test]
+    fn test_compare_values() {
+        let mile = Value::new(1.0, Unit::Length(LengthUnit::Mile));
+        let kilometer = Value::new(1.0, Unit::Length(LengthUnit::Kilometer));
+        assert!(mile > kilometer);
+        assert!(kilometer < mile);
+        assert_eq!(mile.compare_to(&kilometer), Ok(Ordering::Greater));
+        assert_eq!(
+            kilometer.compare_to(&Value::new(1000.0, Unit::Length(LengthUnit::Meter))),
+            Ok(Ordering::Equal)
+        );
+
+        let kilogram = Value::new(1.0, Unit::Mass(MassUnit::Kilogram));
+        assert_eq!(mile.partial_cmp(&kilogram), None);
+        assert_eq!(
+            mile.compare_to(&kilogram).unwrap_err().kind(),
+            ConversionErrorKind::UnsupportedConversion
+        );
+
+        let mut lengths = [
+            mile,
+            kilometer,
+            Value::new(3.0, Unit::Length(LengthUnit::Foot)),
+        ];
+        lengths.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(lengths[0].unit, Unit::Length(LengthUnit::Foot));
+        assert_eq!(lengths[2], mile);
+    }
+
+    #[test]
+    fn test_interpolate() {
+        let celsius = |value| Value::new(value, Unit::Temperature(TemperatureUnit::Celsius));
+        assert_eq!(
+            interpolate(0.5, &celsius(0.0), &celsius(100.0)).unwrap(),
+            celsius(50.0)
+        );
+
+        // the result is expressed in the unit of the start value
+        let start = Value::new(0.0, Unit::Length(LengthUnit::Meter));
+        let end = Value::new(1.0, Unit::Length(LengthUnit::Kilometer));
+        let quarter = interpolate(0.25, &start, &end).unwrap();
+        assert_eq!(quarter.unit, Unit::Length(LengthUnit::Meter));
+        assert!((quarter.value.unwrap() - 250.0).abs() < 1e-9);
+
+        assert_eq!(
+            interpolate(1.5, &celsius(0.0), &celsius(100.0))
+                .unwrap_err()
+                .kind,
+            ConversionErrorKind::OutOfRange
+        );
+        assert!(interpolate(0.5, &start, &celsius(100.0)).is_err());
+    }
+
+    #[test]
+    fn test_display_natural() {
+        let meters = |value| Value::new(value, Unit::Length(LengthUnit::Meter));
+        assert_eq!(meters(1500.0).display_natural(UnitSystem::Metric), "1.5 km");
+        assert_eq!(meters(0.005).display_natural(UnitSystem::Metric), "5 mm");
+        assert_eq!(meters(5.0).display_natural(UnitSystem::Metric), "5 m");
+        assert_eq!(meters(-0.25).display_natural(UnitSystem::Metric), "-25 cm");
+        assert_eq!(meters(0.0).display_natural(UnitSystem::Metric), "0 m");
+
+        let feet = |value| Value::new(value, Unit::Length(LengthUnit::Foot));
+        assert_eq!(feet(3.5).display_natural(UnitSystem::Imperial), "3 ft 6 in");
+        assert_eq!(feet(4.0).display_natural(UnitSystem::Imperial), "4 ft");
+        assert_eq!(feet(0.5).display_natural(UnitSystem::Imperial), "6 in");
+        assert_eq!(
+            meters(1.0).display_natural(UnitSystem::Imperial),
+            "3 ft 3.37 in"
+        );
+
+        let pounds = Value::new(2.5, Unit::Mass(MassUnit::Pound));
+        assert_eq!(pounds.display_natural(UnitSystem::Imperial), "2 lb 8 oz");
+
+        // categories without a system keep their unit
+        let seconds = Value::new(90.0, Unit::Time(TimeUnit::Second));
+        assert_eq!(
+            seconds.display_natural(UnitSystem::Metric),
+            seconds.to_string()
+        );
+    }
+
+    #[test]
+    fn test_prefixed_abbreviations_do_not_collide() {
+        assert_eq!(
+            "mm".parse::<Unit>(),
+            Ok(Unit::Length(LengthUnit::Millimeter))
+        );
+        assert_eq!("m".parse::<Unit>(), Ok(Unit::Length(LengthUnit::Meter)));
+        assert_eq!("mg".parse::<Unit>(), Ok(Unit::Mass(MassUnit::Milligram)));
+        assert_eq!("g".parse::<Unit>(), Ok(Unit::Mass(MassUnit::Gram)));
+        assert_eq!(Unit::Length(LengthUnit::Millimeter).abbreviation(), "mm");
+        assert_eq!(Unit::Mass(MassUnit::Milligram).abbreviation(), "mg");
+    }
+
+    #[test]
+    fn test_conversion_error_factories() {
+        assert_eq!(
+            ConversionError::with_suggestion("kmm", Some("km")).to_string(),
+            "Conversion error: Invalid unit: 'kmm'. Did you mean 'km'?"
+        );
+        assert_eq!(
+            ConversionError::with_suggestion("xyz", None).message(),
+            "Invalid unit: 'xyz'"
+        );
+        assert_eq!(
+            ConversionError::with_suggestion("xyz", None).kind(),
+            ConversionErrorKind::ParseError
+        );
+
+        let error = ConversionError::unsupported_conversion(
+            &Unit::Length(LengthUnit::Meter),
+            &Unit::Mass(MassUnit::Kilogram),
+        );
+        assert_eq!(error.kind(), ConversionErrorKind::UnsupportedConversion);
+        assert_eq!(
+            error.message(),
+            "Cannot convert from meter (m) to kilogram (kg)"
+        );
+        assert_eq!(
+            Value::new(1.0, Unit::Length(LengthUnit::Meter))
+                .convert_to(&Unit::Mass(MassUnit::Kilogram)),
+            Err(error)
+        );
+
+        assert_eq!(
+            (Value::new(1.0, Unit::Length(LengthUnit::Meter)) / 0.0).to_si(),
+            Err(ConversionError::missing_value())
+        );
+        assert_eq!(
+            ConversionError::out_of_range("5 m", 0, 1).message(),
+            "5 m is outside of the range [0, 1]"
+        );
+    }
+
+    #[test]
+    fn test_conversion_error_is_std_error() {
+        fn boxed() -> Result<(), Box<dyn std::error::Error>> {
+            Value::new(1.0, Unit::Length(LengthUnit::Meter))
+                .convert_to(&Unit::Mass(MassUnit::Kilogram))?;
+            Ok(())
+        }
+        let e = boxed().unwrap_err();
+        assert!(e.to_string().starts_with("Conversion error"));
+
+        let e = ConversionError::with_source(
+            ConversionErrorKind::ParseError,
+            "wrapped",
+            ConversionError::new(ConversionErrorKind::ParseError, "inner"),
+        );
+        assert_eq!(
+            std::error::Error::source(&e).unwrap().to_string(),
+            "Conversion error: inner"
+        );
+    }
+
+    #[test]
+    fn test_value_from_str() {
+        assert_eq!(
+            "100 km".parse::<Value>(),
+            Ok(Value::new(100.0, Unit::Length(LengthUnit::Kilometer)))
+        );
+        assert_eq!(
+            "-5 °C".parse::<Value>(),
+            Ok(Value::new(
+                -5.0,
+                Unit::Temperature(TemperatureUnit::Celsius)
+            ))
+        );
+        assert_eq!(
+            "1.5e3 kg".parse::<Value>(),
+            Ok(Value::new(1500.0, Unit::Mass(MassUnit::Kilogram)))
+        );
+
+        let e = "abc km".parse::<Value>().unwrap_err();
+        assert!(e.to_string().contains("Invalid value"));
+
+        let e = "100 xyz".parse::<Value>().unwrap_err();
+        assert!(e.to_string().contains("Invalid unit"));
+
+        assert!("100".parse::<Value>().is_err());
+    }
+
+    #[test]
+    fn test_unit_category() {
+        assert_eq!(
+            Unit::Length(LengthUnit::Meter).category(),
+            UnitCategory::Length
+        );
+        assert_eq!(
+            Unit::Currency(CurrencyUnit::EUR).category(),
+            UnitCategory::Currency
+        );
+
+        let mass_units: Vec<Unit> = Unit::iter_by_category(UnitCategory::Mass).collect();
+        assert_eq!(mass_units.len(), MassUnit::iter().count());
+        assert!(mass_units
+            .iter()
+            .all(|unit| unit.category() == UnitCategory::Mass));
+    }
+
+    #[test]
+    fn test_value_add_and_sub() {
+        let a = Value::new(5.0, Unit::Length(LengthUnit::Meter));
+        let b = Value::new(100.0, Unit::Length(LengthUnit::Centimeter));
+        assert_eq!(a + b, Ok(Value::new(6.0, Unit::Length(LengthUnit::Meter))));
+        assert_eq!(a - b, Ok(Value::new(4.0, Unit::Length(LengthUnit::Meter))));
+
+        let c = Value::new(3.0, Unit::Mass(MassUnit::Kilogram));
+        assert!((a + c).is_err());
+        assert!((a - c).is_err());
+    }
+
+    #[test]
+    fn test_value_sub_with_context() {
+        let provider = crate::core::providers::MockExchangeRateProvider {
+            rates: HashMap::from([(CurrencyUnit::USD, 1.0), (CurrencyUnit::EUR, 2.0)]),
+        };
+        let ctx = ExecutionContext::new(crate::core::currency::ConversionCache::with_config(
+            crate::core::currency::CacheConfig {
+                db_path: ":memory:".into(),
+                provider: std::sync::Arc::new(provider),
+                ..Default::default()
+            },
+        ));
+        let a = Value::new(30.0, Unit::Currency(CurrencyUnit::EUR));
+        let b = Value::new(10.0, Unit::Currency(CurrencyUnit::USD));
+        assert_eq!(
+            a.sub_with(&b, &ctx),
+            Ok(Value::new(10.0, Unit::Currency(CurrencyUnit::EUR)))
+        );
+    }
+
+    #[test]
+    fn test_value_display() {
+        let v = Value::new(1.0, Unit::Length(LengthUnit::Kilometer));
+        assert_eq!(format!("{}", v), "1 km");
+        assert_eq!(v.display_compact().to_string(), "1 km");
+        assert_eq!(v.display_verbose().to_string(), "1 kilometer (km)");
+
+        let options = FormatOptions {
+            verbose: true,
+            ..Default::default()
+        };
+        assert_eq!(v.format(&options), "1 kilometer (km)");
+
+        let v = Value::new(2.0, Unit::Currency(CurrencyUnit::EUR));
+        assert_eq!(v.to_string(), "2 EUR");
+    }
+
+    #[test]
+    fn test_value_with_precision() {
+        let v = Value::new(0.9144, Unit::Length(LengthUnit::Meter));
+        assert_eq!(v.with_precision(4).to_string(), "0.9144 m");
+        assert_eq!(v.with_precision(2).to_string(), "0.91 m");
+
+        let v = Value::new(1.0, Unit::Length(LengthUnit::Yard))
+            .convert_to(&Unit::Length(LengthUnit::Meter))
+            .unwrap();
+        assert_eq!(v.with_precision(6).to_string(), "0.914400 m");
+
+        let options = FormatOptions {
+            verbose: true,
+            precision: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(v.format(&options), "0.9 meter (m)");
+    }
+
+    #[test]
+    fn test_currency_codes_from_str() {
+        let codes = [
+            ("CAD", CurrencyUnit::CAD),
+            ("CHF", CurrencyUnit::CHF),
+            ("CNY", CurrencyUnit::CNY),
+            ("INR", CurrencyUnit::INR),
+            ("BRL", CurrencyUnit::BRL),
+            ("MXN", CurrencyUnit::MXN),
+            ("SEK", CurrencyUnit::SEK),
+            ("NOK", CurrencyUnit::NOK),
+            ("DKK", CurrencyUnit::DKK),
+            ("PLN", CurrencyUnit::PLN),
+            ("HUF", CurrencyUnit::HUF),
+            ("CZK", CurrencyUnit::CZK),
+            ("TRY", CurrencyUnit::TRY),
+            ("ZAR", CurrencyUnit::ZAR),
+            ("SGD", CurrencyUnit::SGD),
+            ("HKD", CurrencyUnit::HKD),
+            ("NZD", CurrencyUnit::NZD),
+            ("ARS", CurrencyUnit::ARS),
+            ("IDR", CurrencyUnit::IDR),
+            ("RUB", CurrencyUnit::RUB),
+            ("SAR", CurrencyUnit::SAR),
+        ];
+        for (code, unit) in codes {
+            assert_eq!(code.parse::<CurrencyUnit>(), Ok(unit));
+            assert_eq!(unit.to_string(), code);
+        }
+        assert_eq!(CurrencyUnit::iter().count(), 27);
+    }
+
+    #[test]
+    fn test_currency_full_names() {
+        for currency in CurrencyUnit::iter() {
+            assert!(!currency.full_name().is_empty(), "{:?}", currency);
+        }
+        assert_eq!(CurrencyUnit::EUR.full_name(), "Euro");
+        assert_eq!(Unit::Currency(CurrencyUnit::EUR).full_name(), "EUR");
+        assert_eq!(
+            Value::new(5.0, Unit::Currency(CurrencyUnit::EUR)).display_full_currency(),
+            "5 Euro"
+        );
+        assert_eq!(
+            Value::new(5.0, Unit::Length(LengthUnit::Meter)).display_full_currency(),
+            "5 m"
+        );
+    }
+
+    #[test]
+    fn test_currency_symbols() {
+        assert_eq!(CurrencyUnit::EUR.symbol(), "€");
+        assert_eq!(CurrencyUnit::USD.symbol(), "$");
+        assert_eq!("€".parse::<CurrencyUnit>(), Ok(CurrencyUnit::EUR));
+        assert_eq!("$".parse::<Unit>(), Ok(Unit::Currency(CurrencyUnit::USD)));
+        assert_eq!("A$".parse::<CurrencyUnit>(), Ok(CurrencyUnit::AUD));
+        // shared or plain letter symbols do not parse
+        assert!("kr".parse::<CurrencyUnit>().is_err());
+        assert_eq!(
+            "R".parse::<Unit>(),
+            Ok(Unit::Temperature(TemperatureUnit::Rankine))
+        );
+
+        assert_eq!(format!("{:#}", CurrencyUnit::USD), "USD ($)");
+        assert_eq!(format!("{:#}", CurrencyUnit::CHF), "CHF");
+        assert_eq!(CurrencyUnit::USD.to_string(), "USD");
+        assert_eq!(Unit::Currency(CurrencyUnit::GBP).abbreviation(), "GBP");
+
+        let value = |amount, currency| Value::new(amount, Unit::Currency(currency));
+        assert_eq!(value(5.0, CurrencyUnit::USD).display_with_symbol(), "$5");
+        assert_eq!(value(2.5, CurrencyUnit::EUR).display_with_symbol(), "€2.5");
+        assert_eq!(
+            value(100.0, CurrencyUnit::SEK).display_with_symbol(),
+            "100 kr"
+        );
+        assert_eq!(
+            Value::new(1.0, Unit::Length(LengthUnit::Meter)).display_with_symbol(),
+            "1 m"
+        );
+    }
+
+    #[test]
+    fn test_conversion_table() {
+        let table = Value::new(1.0, Unit::Length(LengthUnit::Meter)).to_conversion_table();
+        let value_in = |unit: LengthUnit| {
+            let (_, result) = table
+                .conversions
+                .iter()
+                .find(|(u, _)| *u == Unit::Length(unit))
+                .unwrap();
+            result.as_ref().unwrap().value.unwrap()
+        };
+
+        assert_eq!(table.conversions.len(), LengthUnit::iter().count());
+        assert_eq!(value_in(LengthUnit::Meter), 1.0);
+        assert_eq!(value_in(LengthUnit::Centimeter), 100.0);
+        assert_eq!(value_in(LengthUnit::Kilometer), 0.001);
+        assert!((value_in(LengthUnit::Yard) - 1.0936).abs() < 1e-4);
+        assert!((value_in(LengthUnit::Foot) - 3.2808).abs() < 1e-4);
+        assert!((value_in(LengthUnit::Inch) - 39.3701).abs() < 1e-4);
+
+        let output = table.to_string();
+        assert!(output.starts_with("1 m is equal to:\n"));
+        let row = |name: &str| {
+            output
+                .lines()
+                .find(|line| line.trim_start().starts_with(&format!("{} ", name)))
+                .unwrap()
+        };
+        assert!(row("centimeter").ends_with(" 100.000000"));
+        assert!(row("kilometer").ends_with(" 0.001000"));
+        assert!(row("foot").ends_with(" 3.280840"));
+
+        // names, abbreviations and values start and end in the same columns
+        let position = |line: &str, part: &str| {
+            line.chars().count() - line[line.find(part).unwrap()..].chars().count()
+        };
+        assert_eq!(
+            position(row("centimeter"), "(cm)"),
+            position(row("foot"), "(ft)")
+        );
+        assert_eq!(
+            row("centimeter").chars().count(),
+            row("kilometer").chars().count()
+        );
+        assert_eq!(
+            row("foot").chars().count(),
+            row("light-year").chars().count()
+        );
+    }
+
+    #[test]
+    fn test_unit_abbreviation_and_full_name() {
+        let km = Unit::Length(LengthUnit::Kilometer);
+        assert_eq!(km.abbreviation(), "km");
+        assert_eq!(km.full_name(), "kilometer");
+        assert_eq!(TemperatureUnit::Celsius.abbreviation(), "°C");
+
+        for unit in Unit::get_all_units() {
+            assert!(!unit.abbreviation().is_empty());
+            assert!(!unit.full_name().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_compatible_units() {
+        let meter = Unit::Length(LengthUnit::Meter);
+        let compatible = meter.compatible_units();
+        assert_eq!(compatible.len(), LengthUnit::iter().count() - 1);
+        assert!(!compatible.contains(&meter));
+        assert!(compatible
+            .iter()
+            .all(|unit| unit.category() == UnitCategory::Length));
+        assert!(!compatible.contains(&Unit::Mass(MassUnit::Kilogram)));
+        assert!(!compatible.contains(&Unit::Currency(CurrencyUnit::USD)));
+    }
+
+    #[test]
+    fn test_ton_variants() {
+        let to_kg = |unit: MassUnit| {
+            Value::new(1.0, Unit::Mass(unit))
+                .convert_to(&Unit::Mass(MassUnit::Kilogram))
+                .unwrap()
+                .value
+                .unwrap()
+        };
+        assert_eq!(to_kg(MassUnit::MetricTon), 1000.0);
+        assert_eq!(to_kg(MassUnit::ShortTon), 907.18474);
+        assert_eq!(to_kg(MassUnit::LongTon), 1016.0469088);
+        assert_ne!(to_kg(MassUnit::ShortTon), to_kg(MassUnit::MetricTon));
+        assert_ne!(to_kg(MassUnit::LongTon), to_kg(MassUnit::MetricTon));
+        assert!((to_kg(MassUnit::Stone) - 6.35029318).abs() < 1e-9);
+
+        assert_eq!("t".parse::<MassUnit>(), Ok(MassUnit::MetricTon));
+        assert_eq!("ton".parse::<MassUnit>(), Ok(MassUnit::MetricTon));
+        assert_eq!("tonne".parse::<MassUnit>(), Ok(MassUnit::MetricTon));
+        assert_eq!("st".parse::<MassUnit>(), Ok(MassUnit::ShortTon));
+        assert_eq!("short ton".parse::<MassUnit>(), Ok(MassUnit::ShortTon));
+        assert_eq!("lt".parse::<MassUnit>(), Ok(MassUnit::LongTon));
+        assert_eq!("stone".parse::<MassUnit>(), Ok(MassUnit::Stone));
+    }
+
+    #[test]
+    fn test_additional_length_units() {
+        let convert = |value: f64, from: LengthUnit, to: LengthUnit| {
+            Value::new(value, Unit::Length(from))
+                .convert_to(&Unit::Length(to))
+                .unwrap()
+                .value
+                .unwrap()
+        };
+        assert_eq!(convert(1.0, LengthUnit::Mile, LengthUnit::Foot), 5280.0);
+        assert_eq!(
+            convert(1.0, LengthUnit::NauticalMile, LengthUnit::Meter),
+            1852.0
+        );
+        assert_eq!(convert(1.0, LengthUnit::League, LengthUnit::Mile), 3.0);
+        assert_eq!(convert(1.0, LengthUnit::Fathom, LengthUnit::Foot), 6.0);
+        assert!((convert(1.0, LengthUnit::Meter, LengthUnit::Nanometer) - 1e9).abs() < 1e-3);
+
+        assert_eq!("um".parse::<LengthUnit>(), Ok(LengthUnit::Micrometer));
+        assert_eq!("\u{b5}m".parse::<LengthUnit>(), Ok(LengthUnit::Micrometer));
+        assert_eq!("\u{3bc}m".parse::<LengthUnit>(), Ok(LengthUnit::Micrometer));
+        assert_eq!(
+            "nm".parse::<Unit>(),
+            Ok(Unit::Length(LengthUnit::Nanometer))
+        );
+        assert_eq!("AU".parse::<LengthUnit>(), Ok(LengthUnit::AstronomicalUnit));
+        assert_eq!("ly".parse::<LengthUnit>(), Ok(LengthUnit::LightYear));
+    }
+
+    #[test]
+    fn test_small_mass_units() {
+        let convert = |value: f64, from: MassUnit, to: MassUnit| {
+            Value::new(value, Unit::Mass(from))
+                .convert_to(&Unit::Mass(to))
+                .unwrap()
+                .value
+                .unwrap()
+        };
+        assert!((convert(1.0, MassUnit::Carat, MassUnit::Milligram) - 200.0).abs() < 1e-9);
+        assert!((convert(1.0, MassUnit::Gram, MassUnit::Microgram) - 1e6).abs() < 1e-6);
+        let dalton = convert(1.0, MassUnit::AtomicMassUnit, MassUnit::Kilogram);
+        assert!((dalton - 1.6605390666e-27).abs() / 1.6605390666e-27 < 1e-10);
+
+        assert_eq!("ug".parse::<MassUnit>(), Ok(MassUnit::Microgram));
+        assert_eq!("\u{b5}g".parse::<MassUnit>(), Ok(MassUnit::Microgram));
+        assert_eq!("\u{3bc}g".parse::<MassUnit>(), Ok(MassUnit::Microgram));
+        assert_eq!(
+            "Da".parse::<Unit>(),
+            Ok(Unit::Mass(MassUnit::AtomicMassUnit))
+        );
+        assert_eq!(
+            "u".parse::<Unit>(),
+            Ok(Unit::Mass(MassUnit::AtomicMassUnit))
+        );
+    }
+
+    #[test]
+    fn test_value_serde_round_trip() {
+        let v = Value::new(1.0, Unit::Length(LengthUnit::Kilometer));
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(json, r#"{"value":1.0,"unit":"km"}"#);
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), v);
+
+        let v = Value::new(5.0, Unit::Currency(CurrencyUnit::EUR));
+        let json = serde_json::to_string(&v).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), v);
+
+        assert!(serde_json::from_str::<Value>(r#"{"value":1.0,"unit":"foo"}"#).is_err());
+    }
+
+    #[test]
+    fn test_unit_serde_round_trip() {
+        for unit in Unit::get_all_units() {
+            let json = serde_json::to_string(&unit).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Unit>(&json).unwrap(),
+                unit,
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_conversion_error_kind() {
+        let error = Value::new(1.0, Unit::Length(LengthUnit::Meter))
+            .convert_to(&Unit::Mass(MassUnit::Kilogram))
+            .unwrap_err();
+        assert_eq!(error.kind(), ConversionErrorKind::UnsupportedConversion);
+
+        let error = "1 xyz".parse::<Value>().unwrap_err();
+        assert_eq!(error.kind(), ConversionErrorKind::ParseError);
+        let error = "one m".parse::<Value>().unwrap_err();
+        assert_eq!(error.kind(), ConversionErrorKind::ParseError);
+
+        let error = Value::new(-1.0, Unit::Temperature(TemperatureUnit::Kelvin))
+            .convert_to(&Unit::Temperature(TemperatureUnit::Celsius))
+            .unwrap_err();
+        assert_eq!(error.kind(), ConversionErrorKind::NegativeTemperature);
+
+        let error = Value::new(
+            0.0,
+            Unit::FuelEfficiency(FuelEfficiencyUnit::LitersPer100km),
+        )
+        .convert_to(&Unit::FuelEfficiency(
+            FuelEfficiencyUnit::KilometersPerLiter,
+        ))
+        .unwrap_err();
+        assert_eq!(error.kind(), ConversionErrorKind::DivisionByZero);
+    }
+
+    #[test]
+    fn test_clamp() {
+        let bar = Unit::Pressure(PressureUnit::Bar);
+        let value = Value::new(5.0, bar);
+        assert_eq!(value.clamp(0.0, 10.0), Ok(value));
+        assert_eq!(value.clamp(5.0, 5.0), Ok(value));
+        assert_eq!(
+            value.clamp(6.0, 10.0).unwrap_err().kind(),
+            ConversionErrorKind::OutOfRange
+        );
+        assert_eq!(
+            value.assert_in_range(0.0, 4.0).unwrap_err().to_string(),
+            "Conversion error: 5 bar is outside of the range [0, 4]"
+        );
+
+        let doubled = value.assert_in_range(0.0, 10.0).map(|v| v.scale(2.0));
+        assert_eq!(doubled, Ok(Value::new(10.0, bar)));
+    }
+
+    #[test]
+    fn test_physical_range() {
+        assert_eq!(
+            Unit::Temperature(TemperatureUnit::Kelvin).physical_range(),
+            Some((0.0, f64::INFINITY))
+        );
+        assert_eq!(Unit::Length(LengthUnit::Meter).physical_range(), None);
+
+        // the lowest possible temperature converts in every direction
+        for from in TemperatureUnit::iter() {
+            let (min, _) = Unit::Temperature(from).physical_range().unwrap();
+            for to in TemperatureUnit::iter() {
+                assert!(Value::new(min, Unit::Temperature(from))
+                    .convert_to(&Unit::Temperature(to))
+                    .is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_conversion_error_serde() {
+        let error = ConversionError::new(ConversionErrorKind::DivisionByZero, "Division by zero");
+        let json = serde_json::to_string(&error).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"DivisionByZero","message":"Division by zero"}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<ConversionError>(&json).unwrap(),
+            error
+        );
+    }
+
+    #[test]
+    fn test_conversion_chain() {
+        let kg = Unit::Mass(MassUnit::Kilogram);
+        let lb = Unit::Mass(MassUnit::Pound);
+        let oz = Unit::Mass(MassUnit::Ounce);
+        let chain = ConversionChain::new(vec![kg, lb, oz]).unwrap();
+        let values = chain.apply(1.0).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0], Value::new(1.0, kg));
+        assert!(values[1]
+            .is_approximately_equal(&Value::new(2.20462, lb), 1e-5)
+            .unwrap());
+        assert!(values[2]
+            .is_approximately_equal(&Value::new(35.274, oz), 1e-5)
+            .unwrap());
+        assert_eq!(chain.iter(1.0).count(), 3);
+
+        assert_eq!(
+            ConversionChain::new(vec![kg, Unit::Length(LengthUnit::Meter), oz])
+                .unwrap_err()
+                .kind(),
+            ConversionErrorKind::UnsupportedConversion
+        );
+        assert!(ConversionChain::new(vec![kg]).is_err());
+    }
+
+    #[test]
+    fn test_from_locale() {
+        let kilometer = Unit::Length(LengthUnit::Kilometer);
+        assert_eq!(Unit::from_locale("fr", "kilomètre"), Ok(kilometer));
+        assert_eq!(Unit::from_locale("de", "Kilometer"), Ok(kilometer));
+        assert_eq!(Unit::from_locale("es", "kilómetros"), Ok(kilometer));
+        assert_eq!(
+            Unit::from_locale("fr", "tonne"),
+            Ok(Unit::Mass(MassUnit::MetricTon))
+        );
+        assert_eq!(
+            Unit::from_locale("en", "litre"),
+            Ok(Unit::Volume(VolumeUnit::Liter))
+        );
+        // the default names are understood in every locale
+        assert_eq!(Unit::from_locale("fr", "km"), Ok(kilometer));
+        assert_eq!(Unit::from_locale("it", "km"), Ok(kilometer));
+        assert_eq!(
+            Unit::from_locale("fr", "xyz").unwrap_err().kind(),
+            ConversionErrorKind::ParseError
+        );
+    }
+
+    #[test]
+    fn test_all_units() {
+        assert_eq!(Unit::all_units(), Unit::get_all_units().as_slice());
+        assert_eq!(Unit::unit_count(), Unit::all_units().len());
+        assert_eq!(
+            Unit::unit_count(),
+            UnitCategory::iter()
+                .map(|category| Unit::iter_by_category(category).count())
+                .sum::<usize>()
+        );
+        // built once
+        assert!(std::ptr::eq(Unit::all_units(), Unit::all_units()));
+    }
+
+    #[test]
+    fn test_metric_and_imperial() {
+        for unit in [
+            LengthUnit::Meter,
+            LengthUnit::Centimeter,
+            LengthUnit::Kilometer,
+            LengthUnit::Millimeter,
+            LengthUnit::Micrometer,
+            LengthUnit::Nanometer,
+        ] {
+            assert!(Unit::Length(unit).is_metric());
+            assert!(!Unit::Length(unit).is_imperial());
+        }
+        assert!(Unit::Length(LengthUnit::Foot).is_imperial());
+        assert!(!Unit::Length(LengthUnit::Foot).is_metric());
+
+        let nautical_mile = Unit::Length(LengthUnit::NauticalMile);
+        assert!(!nautical_mile.is_metric());
+        assert!(!nautical_mile.is_imperial());
+
+        let usd = Unit::Currency(CurrencyUnit::USD);
+        assert!(!usd.is_metric());
+        assert!(!usd.is_imperial());
+
+        assert!(Unit::filter_metric().iter().all(Unit::is_metric));
+        assert!(Unit::filter_imperial().contains(&Unit::Mass(MassUnit::Stone)));
+        assert!(!Unit::get_all_units()
+            .iter()
+            .any(|unit| unit.is_metric() && unit.is_imperial()));
+    }
+
+    #[test]
+    fn test_unit_category_from_str() {
+        assert_eq!("Length".parse::<UnitCategory>(), Ok(UnitCategory::Length));
+        assert_eq!("mass".parse::<UnitCategory>(), Ok(UnitCategory::Mass));
+        assert_eq!(
+            "fuel_efficiency".parse::<UnitCategory>(),
+            Ok(UnitCategory::FuelEfficiency)
+        );
+        assert!("weight".parse::<UnitCategory>().is_err());
     }
 }
@@ -0,0 +1,34 @@
+pub mod core;
+
+pub use crate::core::commands::{
+    execute_batch, process_batch, Command, CommandResult, Conversion, CsvFormatter, CSV_HEADER,
+};
+pub use crate::core::context::ExecutionContext;
+pub use crate::core::currency::{APIError, CacheConfig, ConversionCache};
+pub use crate::core::locale::LocaleUnitMap;
+pub use crate::core::providers::{
+    get_api_key, store_api_key, ExchangeRateProvider, FrankfurterProvider,
+    MockExchangeRateProvider, OpenExchangeRatesProvider, APP_ID_VAR, KEYRING_SERVICE, KEYRING_USER,
+};
+pub use crate::core::units::{
+    interpolate, AngleUnit, AreaUnit, ChainValues, ConversionChain, ConversionError,
+    ConversionErrorKind, ConversionResult, ConversionTable, Convertable, CurrencyUnit,
+    DataTransferRateUnit, DataUnit, EnergyUnit, FormatOptions, FormattedValue, FuelEfficiencyUnit,
+    LengthUnit, LinearUnit, MassUnit, NumeralSystem, PowerUnit, PressureUnit, SpeedUnit,
+    TemperatureUnit, TimeUnit, Unit, UnitCategory, UnitSystem, Unitlike, Value, VolumeUnit,
+};
+
+/// Convert a value from one unit to another and return only the numeric result.
+pub fn convert(value: f64, from: Unit, to: Unit) -> ConversionResult<f64> {
+    Unit::convert(value, &from, &to, ExecutionContext::global())
+}
+
+/// Like `convert`, without the per-thread cache of conversion factors.
+pub fn convert_uncached(value: f64, from: Unit, to: Unit) -> ConversionResult<f64> {
+    Unit::convert_uncached(value, &from, &to, ExecutionContext::global())
+}
+
+/// Convert a value from one unit to another and return the resulting `Value`.
+pub fn convert_value(value: f64, from: Unit, to: Unit) -> ConversionResult<Value> {
+    Value::new(value, from).convert_to(&to)
+}
@@ -1,9 +1,71 @@
-mod core;
 mod ui;
+use crate::ui::args::Args;
 use crate::ui::cli::Cli;
+use crate::ui::config::Config;
+#[cfg(feature = "http-server")]
+use crate::ui::http::HttpServer;
+use crate::ui::tui::Tui;
 use crate::ui::ui::Interface;
 
-fn main() {
-    let cli = Cli::new();
-    cli.interact();
+use clap::Parser;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::process::ExitCode;
+use unit_conv::{CsvFormatter, ExecutionContext};
+
+fn main() -> ExitCode {
+    let mut args = Args::parse();
+    let config = Config::load(&Config::default_path()).unwrap_or_else(|e| {
+        eprintln!("{}, using the defaults", e);
+        Config::default()
+    });
+    config.apply(&mut args);
+
+    if let Some(path) = &args.batch {
+        return run_batch(path, args.csv, &config.context());
+    }
+
+    #[cfg(feature = "http-server")]
+    if args.serve {
+        HttpServer::new(args, config).interact();
+        return ExitCode::SUCCESS;
+    }
+
+    if args.tui {
+        Tui::new(args, config).interact();
+    } else {
+        Cli::new(args, config).interact();
+    }
+    ExitCode::SUCCESS
+}
+
+/// Process the batch file (or stdin for "-") and print one result per line, optionally as CSV.
+fn run_batch(path: &Path, csv: bool, context: &ExecutionContext) -> ExitCode {
+    let results = if path == Path::new("-") {
+        unit_conv::execute_batch(io::stdin().lock(), context)
+    } else {
+        match File::open(path) {
+            Ok(file) => unit_conv::execute_batch(BufReader::new(file), context),
+            Err(e) => {
+                eprintln!("Cannot open {}: {}", path.display(), e);
+                return ExitCode::FAILURE;
+            }
+        }
+    };
+
+    if csv {
+        let mut formatter = CsvFormatter::new(io::stdout().lock());
+        for result in &results {
+            if let Err(e) = formatter.write(result) {
+                eprintln!("Cannot write CSV: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+    } else {
+        for result in results {
+            println!("{}", result.output);
+        }
+    }
+    ExitCode::SUCCESS
 }
@@ -0,0 +1,234 @@
+use crate::ui::style::ColorMode;
+use clap::Parser;
+use std::path::PathBuf;
+use unit_conv::{Command, NumeralSystem, Unit, Value};
+
+/// Command line arguments. Without any of them the interactive mode is started.
+#[derive(Parser, Debug, Default)]
+#[command(version, about)]
+pub struct Args {
+    /// Value to convert (requires --from and --to).
+    #[arg(short, long, allow_negative_numbers = true)]
+    pub value: Option<f64>,
+    /// Unit to convert from.
+    #[arg(short, long)]
+    pub from: Option<String>,
+    /// Unit to convert to.
+    #[arg(short, long)]
+    pub to: Option<String>,
+    /// List all available units.
+    #[arg(long)]
+    pub units: bool,
+    /// List the units with their abbreviations, optionally only those of one category.
+    #[arg(short, long, value_name = "CATEGORY")]
+    pub list: Option<Option<String>>,
+    /// Print results as JSON.
+    #[arg(long)]
+    pub json: bool,
+    /// Show full unit names in results.
+    #[arg(long)]
+    pub verbose: bool,
+    /// Number of decimal places in results [default: 6].
+    #[arg(long, value_name = "N")]
+    pub precision: Option<usize>,
+    /// Start the full screen terminal interface instead of the line based one.
+    #[arg(long)]
+    pub tui: bool,
+    /// Append conversion results to a file.
+    #[arg(short, long, value_name = "FILE")]
+    pub output_file: Option<PathBuf>,
+    /// Do not print conversion results, only write them to --output-file.
+    #[arg(short, long, requires = "output_file")]
+    pub quiet: bool,
+    /// Process one expression per line from a file ("-" for stdin).
+    #[arg(short, long, value_name = "FILE")]
+    pub batch: Option<PathBuf>,
+    /// Print the batch results as CSV, takes precedence over --json.
+    #[arg(long, requires = "batch")]
+    pub csv: bool,
+    /// Always color the output, even when it is not printed to a terminal.
+    #[arg(long, conflicts_with = "no_color")]
+    pub color: bool,
+    /// Never color the output, like setting NO_COLOR.
+    #[arg(long)]
+    pub no_color: bool,
+    /// Print whole numbers in results as hexadecimal, e.g. 0x400.
+    #[arg(long, conflicts_with_all = ["binary", "octal"])]
+    pub hex: bool,
+    /// Print whole numbers in results as binary, e.g. 0b101.
+    #[arg(long, conflicts_with = "octal")]
+    pub binary: bool,
+    /// Print whole numbers in results as octal, e.g. 0o17.
+    #[arg(long)]
+    pub octal: bool,
+    /// Serve conversions over HTTP on localhost instead of starting the interactive mode.
+    #[cfg(feature = "http-server")]
+    #[arg(long)]
+    pub serve: bool,
+    /// Port of the HTTP server [default: 8080].
+    #[cfg(feature = "http-server")]
+    #[arg(long, value_name = "N", requires = "serve")]
+    pub port: Option<u16>,
+}
+
+impl Args {
+    /// Build the command requested through flags, if any.
+    /// Returns `None` when the interactive mode should be started.
+    pub fn command(&self) -> Option<Result<Command, String>> {
+        if self.units {
+            return Some(Ok(Command::Units));
+        }
+        match &self.list {
+            Some(None) => return Some(Ok(Command::Units)),
+            Some(Some(category)) => {
+                return Some(category.parse().map(Command::CategoryUnits));
+            }
+            None => {}
+        }
+
+        match (self.value, &self.from, &self.to) {
+            (None, None, None) => None,
+            (Some(value), Some(from), Some(to)) => Some(Self::conversion(value, from, to)),
+            _ => Some(Err(
+                "The flags --value, --from and --to must be used together.".to_string(),
+            )),
+        }
+    }
+
+    /// Color mode selected through the flags, falling back to the environment.
+    pub fn color_mode(&self) -> ColorMode {
+        if self.no_color {
+            ColorMode::Never
+        } else if self.color {
+            ColorMode::Always
+        } else {
+            ColorMode::from_env()
+        }
+    }
+
+    /// Numeral system results are printed in, decimal unless --hex, --binary or --octal is given.
+    pub fn numeral_system(&self) -> NumeralSystem {
+        if self.hex {
+            NumeralSystem::Hexadecimal
+        } else if self.binary {
+            NumeralSystem::Binary
+        } else if self.octal {
+            NumeralSystem::Octal
+        } else {
+            NumeralSystem::Decimal
+        }
+    }
+
+    fn conversion(value: f64, from: &str, to: &str) -> Result<Command, String> {
+        let from_unit = Self::unit(from)?;
+        let to_unit = Self::unit(to)?;
+        Ok(Command::Convert(Value::new(value, from_unit), to_unit))
+    }
+
+    /// Parse a unit given through a flag, listing the candidates of an ambiguous name.
+    fn unit(s: &str) -> Result<Unit, String> {
+        Unit::parse_unambiguous(s).map_err(|e| e.message().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unit_conv::{LengthUnit, UnitCategory};
+
+    #[test]
+    fn test_conversion_flags() {
+        let args = Args::try_parse_from(["unit-conv", "-v", "100", "-f", "m", "-t", "km"]).unwrap();
+        assert_eq!(
+            args.command(),
+            Some(Ok(Command::Convert(
+                Value::new(100.0, Unit::Length(LengthUnit::Meter)),
+                Unit::Length(LengthUnit::Kilometer)
+            )))
+        );
+
+        let args =
+            Args::try_parse_from(["unit-conv", "--value", "-40", "--from", "C", "--to", "F"])
+                .unwrap();
+        assert!(matches!(args.command(), Some(Ok(Command::Convert(_, _)))));
+    }
+
+    #[test]
+    fn test_unit_flag_errors() {
+        assert_eq!(
+            Args::unit("t"),
+            Ok(Unit::Mass(unit_conv::MassUnit::MetricTon))
+        );
+        assert_eq!(
+            Args::unit("kilometree"),
+            Err("Invalid unit: 'kilometree'. Did you mean 'kilometer'?".to_string())
+        );
+    }
+
+    #[test]
+    fn test_partial_conversion_flags() {
+        let args = Args::try_parse_from(["unit-conv", "-v", "100", "-f", "m"]).unwrap();
+        assert!(matches!(args.command(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_precision_flag() {
+        let args = Args::try_parse_from(["unit-conv", "--precision", "2"]).unwrap();
+        assert_eq!(args.precision, Some(2));
+
+        let args = Args::try_parse_from(["unit-conv"]).unwrap();
+        assert_eq!(args.precision, None);
+    }
+
+    #[test]
+    fn test_numeral_system_flags() {
+        let args = Args::try_parse_from(["unit-conv", "--hex"]).unwrap();
+        assert_eq!(args.numeral_system(), NumeralSystem::Hexadecimal);
+        let args = Args::try_parse_from(["unit-conv", "--octal"]).unwrap();
+        assert_eq!(args.numeral_system(), NumeralSystem::Octal);
+        let args = Args::try_parse_from(["unit-conv"]).unwrap();
+        assert_eq!(args.numeral_system(), NumeralSystem::Decimal);
+        assert!(Args::try_parse_from(["unit-conv", "--hex", "--binary"]).is_err());
+    }
+
+    #[test]
+    fn test_list_flag() {
+        let args = Args::try_parse_from(["unit-conv", "--list"]).unwrap();
+        assert_eq!(args.command(), Some(Ok(Command::Units)));
+
+        let args = Args::try_parse_from(["unit-conv", "-l", "length"]).unwrap();
+        assert_eq!(
+            args.command(),
+            Some(Ok(Command::CategoryUnits(UnitCategory::Length)))
+        );
+
+        let args = Args::try_parse_from(["unit-conv", "--list", "weight"]).unwrap();
+        assert!(matches!(args.command(), Some(Err(_))));
+    }
+
+    #[test]
+    fn test_output_file_flags() {
+        let args = Args::try_parse_from(["unit-conv", "-o", "results.txt", "--quiet"]).unwrap();
+        assert_eq!(args.output_file, Some(PathBuf::from("results.txt")));
+        assert!(args.quiet);
+
+        assert!(Args::try_parse_from(["unit-conv", "--quiet"]).is_err());
+    }
+
+    #[test]
+    fn test_csv_flag() {
+        let args = Args::try_parse_from(["unit-conv", "--batch", "-", "--csv", "--json"]).unwrap();
+        assert!(args.csv);
+
+        assert!(Args::try_parse_from(["unit-conv", "--csv"]).is_err());
+    }
+
+    #[test]
+    fn test_no_flags() {
+        let args = Args::try_parse_from(["unit-conv"]).unwrap();
+        assert_eq!(args.command(), None);
+
+        let args = Args::try_parse_from(["unit-conv", "--units"]).unwrap();
+        assert_eq!(args.command(), Some(Ok(Command::Units)));
+    }
+}
@@ -1,30 +1,324 @@
-use crate::core::commands::Command;
+use crate::ui::args::Args;
+use crate::ui::completer::UnitCompleter;
+use crate::ui::config::Config;
+use crate::ui::history::{HistoryEntry, HistoryStore};
+use crate::ui::output::OutputWriter;
+use crate::ui::style::OutputStyle;
 use crate::ui::ui::Interface;
+use unit_conv::{
+    CacheConfig, Command, CommandResult, ConversionCache, ExecutionContext, FormatOptions,
+};
 
 use console::Term;
-use dialoguer::Input;
+use indicatif::ProgressBar;
+use rustyline::error::ReadlineError;
+use rustyline::history::DefaultHistory;
+use rustyline::Editor;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-pub struct Cli;
+/// Decimal places used when --precision is not given.
+pub const DEFAULT_PRECISION: usize = 6;
+/// Number of conversions kept in the session history.
+const HISTORY_SIZE: usize = 50;
+
+pub struct Cli {
+    args: Args,
+    precision: Option<usize>,
+    /// Settings from the configuration file.
+    config: Config,
+    /// Currency cache the commands are executed with.
+    context: ExecutionContext,
+    /// Colors of the printed output.
+    style: OutputStyle,
+    /// Previously entered conversions, oldest first.
+    history: VecDeque<HistoryEntry>,
+    /// Entries of the history file and of this session, written back on exit.
+    /// Unlike `history` it is not limited to HISTORY_SIZE entries.
+    saved_history: Vec<HistoryEntry>,
+    /// File the history is kept in between sessions.
+    history_path: PathBuf,
+}
+
+impl Cli {
+    /// Formatting options selected through the flags.
+    fn format_options(&self) -> FormatOptions {
+        FormatOptions {
+            verbose: self.args.verbose,
+            precision: self.precision,
+            base: self.args.numeral_system(),
+        }
+    }
+
+    /// Execute a command with the output format selected by the flags.
+    fn execute(&self, command: &Command) -> CommandResult {
+        if self.args.json {
+            // errors are reported inside the JSON document
+            CommandResult {
+                output: command.execute_json(&self.context),
+                is_error: false,
+                exit: *command == Command::Exit,
+                conversion: None,
+            }
+        } else {
+            command.execute_with(&self.context, &self.format_options())
+        }
+    }
+
+    /// Remember a conversion, dropping the oldest one when the history is full.
+    fn record(&mut self, entry: HistoryEntry) {
+        if self.history.len() == HISTORY_SIZE {
+            self.history.pop_front();
+        }
+        self.saved_history.push(entry.clone());
+        self.history.push_back(entry);
+    }
+
+    /// Parse the input, replacing history recalls (!<n>) with the recalled expression.
+    fn resolve(&self, input: &str) -> Result<(String, Command), String> {
+        match input.parse()? {
+            Command::Recall(n) => {
+                let entry = self
+                    .history
+                    .get(n - 1)
+                    .ok_or_else(|| format!("No history entry {}", n))?;
+                Ok((entry.input.clone(), entry.input.parse()?))
+            }
+            command => Ok((input.to_string(), command)),
+        }
+    }
+
+    /// Handle the history and configuration commands, which need the session state.
+    fn execute_history(&mut self, command: &Command) -> Option<CommandResult> {
+        match command {
+            Command::History if self.args.json => Some(CommandResult::ok(
+                json!({ "history": self.history }).to_string(),
+            )),
+            Command::History => Some(CommandResult::ok(
+                self.history
+                    .iter()
+                    .enumerate()
+                    .map(|(i, entry)| format!("{:>3}: {}", i + 1, entry.input))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )),
+            Command::Config => Some(CommandResult::ok(self.config.to_string())),
+            Command::HistoryClear => {
+                self.history.clear();
+                self.saved_history.clear();
+                match HistoryStore::clear(&self.history_path) {
+                    Ok(()) => Some(CommandResult::ok("History cleared.")),
+                    Err(e) => Some(self.error(&format!("Could not delete history file: {}", e))),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Format an error message with the output format selected by the flags.
+    fn error(&self, message: &str) -> CommandResult {
+        if self.args.json {
+            CommandResult::error(json!({ "error": message }).to_string())
+        } else {
+            CommandResult::error(message)
+        }
+    }
+
+    /// Print the output of a command, colored according to the flags.
+    fn print(&self, term: &Term, result: &CommandResult) {
+        term.write_line(&self.style.result(result)).unwrap();
+    }
+
+    /// Print a conversion result and write it to the output file, depending on the flags.
+    fn emit(&self, term: &Term, output: &mut OutputWriter, result: &CommandResult) {
+        if output.to_stdout() {
+            self.print(term, result);
+        }
+        if let Err(e) = output.write_file(&result.output) {
+            self.print(
+                term,
+                &self.error(&format!("Could not write output file: {}", e)),
+            );
+        }
+    }
+
+    /// Open the output file, falling back to stdout if it cannot be opened.
+    fn open_output(&self, term: &Term) -> OutputWriter {
+        let path = self.args.output_file.as_deref();
+        OutputWriter::open(path, self.args.quiet).unwrap_or_else(|e| {
+            // opening only fails when a path is given
+            let path = path.map(Path::display).unwrap();
+            self.print(term, &self.error(&format!("Cannot open {}: {}", path, e)));
+            OutputWriter::Stdout
+        })
+    }
+}
 
 impl Interface for Cli {
-    fn new() -> Self {
-        Cli {}
+    fn new(args: Args, config: Config) -> Self {
+        let precision = Some(args.precision.unwrap_or(DEFAULT_PRECISION));
+        let style = OutputStyle {
+            color: args.color_mode(),
+        };
+        Cli {
+            args,
+            precision,
+            context: ExecutionContext::new(ConversionCache::with_config(with_spinner(
+                config.cache_config(),
+            ))),
+            config,
+            style,
+            history: VecDeque::with_capacity(HISTORY_SIZE),
+            saved_history: Vec::new(),
+            history_path: HistoryStore::default_path(),
+        }
     }
 
-    fn interact(self) {
+    fn interact(mut self) {
         let term = Term::stdout();
-        term.write_line("Enter a conversion expression (e.g. 100 m -> km) or 'exit' to exit.")
-            .unwrap();
+        let mut output = self.open_output(&term);
+
+        // single-shot mode when the command was given through flags
+        if let Some(command) = self.args.command() {
+            match command {
+                Ok(command @ Command::Convert(..)) => {
+                    self.emit(&term, &mut output, &self.execute(&command))
+                }
+                Ok(command) => self.print(&term, &self.execute(&command)),
+                Err(e) => self.print(&term, &self.error(&e)),
+            }
+            return;
+        }
+
+        term.write_line(&format!(
+            "Enter a conversion expression (e.g. 100 m {} km) or 'exit' to exit.",
+            self.style.arrow()
+        ))
+        .unwrap();
+
+        let mut editor: Editor<UnitCompleter, DefaultHistory> = Editor::new().unwrap();
+        editor.set_helper(Some(UnitCompleter::new()));
+        for entry in HistoryStore::load(&self.history_path) {
+            let _ = editor.add_history_entry(entry.input.as_str());
+            self.record(entry);
+        }
 
         loop {
-            let input: String = Input::new().interact().unwrap();
+            let input = match editor.readline("> ") {
+                Ok(input) => input,
+                Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+                Err(e) => {
+                    self.print(&term, &self.error(&e.to_string()));
+                    break;
+                }
+            };
+            let _ = editor.add_history_entry(input.as_str());
 
-            let command: Result<Command, _> = input.trim().parse();
-            match command {
-                Ok(Command::Exit) => break,
-                Ok(command) => term.write_line(&command.execute()).unwrap(),
-                Err(e) => term.write_line(&e).unwrap(),
+            match self.resolve(input.trim()) {
+                Ok((input, command)) => {
+                    let result = match self.execute_history(&command) {
+                        Some(result) => result,
+                        None => self.execute(&command),
+                    };
+                    if result.exit {
+                        break;
+                    }
+                    if matches!(
+                        command,
+                        Command::Convert(..)
+                            | Command::ConvertAll(_)
+                            | Command::ConvertCompound(..)
+                            | Command::Invert(..)
+                            | Command::Chain(..)
+                            | Command::Add(..)
+                            | Command::Interpolate(..)
+                            | Command::Multi(_)
+                    ) {
+                        self.emit(&term, &mut output, &result);
+                        self.record(HistoryEntry::new(input, &result));
+                    } else {
+                        self.print(&term, &result);
+                    }
+                }
+                Err(e) => self.print(&term, &self.error(&e)),
+            }
+        }
+
+        // a cleared history stays deleted
+        if !self.saved_history.is_empty() {
+            if let Err(e) = HistoryStore::save(&self.saved_history, &self.history_path) {
+                self.print(
+                    &term,
+                    &self.error(&format!("Could not save history: {}", e)),
+                );
+            }
+        }
+    }
+}
+
+/// Show a spinner on stderr while the exchange rates are requested.
+fn with_spinner(config: CacheConfig) -> CacheConfig {
+    let spinner: Arc<Mutex<Option<ProgressBar>>> = Arc::default();
+    let started = Arc::clone(&spinner);
+    CacheConfig {
+        on_fetch_start: Some(Arc::new(move || {
+            let bar = ProgressBar::new_spinner().with_message("Fetching exchange rates...");
+            bar.enable_steady_tick(Duration::from_millis(100));
+            *started.lock().unwrap() = Some(bar);
+        })),
+        on_fetch_end: Some(Arc::new(move |success| {
+            if let Some(bar) = spinner.lock().unwrap().take() {
+                bar.finish_with_message(if success {
+                    "Rates updated."
+                } else {
+                    "Rate fetch failed, using cached data."
+                });
             }
+        })),
+        ..config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_rolls_over() {
+        let mut cli = Cli::new(Args::default(), Config::default());
+        for i in 0..HISTORY_SIZE + 10 {
+            cli.record(HistoryEntry::new(
+                format!("{} m -> km", i),
+                &CommandResult::ok(""),
+            ));
         }
+        assert_eq!(cli.history.len(), HISTORY_SIZE);
+        assert_eq!(cli.history.front().unwrap().input, "10 m -> km");
+        assert_eq!(cli.history.back().unwrap().input, "59 m -> km");
+        // the history file keeps more entries than the session
+        assert_eq!(cli.saved_history.len(), HISTORY_SIZE + 10);
+        assert_eq!(cli.saved_history[0].input, "0 m -> km");
+    }
+
+    #[test]
+    fn test_recall() {
+        let mut cli = Cli::new(Args::default(), Config::default());
+        cli.history_path = std::env::temp_dir().join("unit_conv_cli_recall_history");
+        cli.record(HistoryEntry::new("1 m -> cm", &CommandResult::ok("100 cm")));
+        let (input, command) = cli.resolve("!1").unwrap();
+        assert_eq!(input, "1 m -> cm");
+        assert!(matches!(command, Command::Convert(_, _)));
+        assert!(cli.resolve("!2").is_err());
+
+        assert_eq!(
+            cli.execute_history(&Command::History).unwrap().output,
+            "  1: 1 m -> cm"
+        );
+        cli.execute_history(&Command::HistoryClear);
+        assert!(cli.history.is_empty());
+        assert!(cli.saved_history.is_empty());
     }
 }
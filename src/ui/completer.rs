@@ -0,0 +1,98 @@
+use rustyline::completion::Completer;
+use rustyline::{Context, Helper, Highlighter, Hinter, Validator};
+use unit_conv::Unit;
+
+/// Completes unit names and abbreviations in the interactive mode.
+#[derive(Helper, Hinter, Highlighter, Validator)]
+pub struct UnitCompleter {
+    /// Full names and abbreviations of all units, sorted and without duplicates.
+    names: Vec<String>,
+}
+
+impl UnitCompleter {
+    pub fn new() -> Self {
+        let mut names: Vec<String> = Unit::all_units()
+            .iter()
+            .flat_map(|unit| [unit.full_name(), unit.abbreviation()])
+            .map(String::from)
+            .collect();
+        names.sort();
+        names.dedup();
+        UnitCompleter { names }
+    }
+
+    /// Start of the word under the cursor and the names completing it.
+    /// Only words after the first one that do not look like a number or the arrow are completed.
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(i, c)| i + c.len_utf8());
+        let prefix = &line[start..pos];
+
+        let is_unit_word = start > 0
+            && !prefix.is_empty()
+            && !prefix.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.');
+        if !is_unit_word {
+            return (pos, Vec::new());
+        }
+
+        let matches = self
+            .names
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .cloned()
+            .collect();
+        (start, matches)
+    }
+}
+
+impl Default for UnitCompleter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Completer for UnitCompleter {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        Ok(self.candidates(line, pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_complete_prefix() {
+        let completer = UnitCompleter::new();
+        let (start, matches) = completer.candidates("100 k", 5);
+        assert_eq!(start, 4);
+        assert!(matches.contains(&"kilometer".to_string()));
+        assert!(matches.contains(&"kilogram".to_string()));
+        assert!(matches.contains(&"km".to_string()));
+        assert!(matches.iter().all(|name| name.starts_with('k')));
+
+        let (start, matches) = completer.candidates("100 m -> kilom", 14);
+        assert_eq!(start, 9);
+        assert!(matches.contains(&"kilometer".to_string()));
+        assert!(!matches.contains(&"kilogram".to_string()));
+    }
+
+    #[test]
+    fn test_no_completion_for_numbers_and_arrow() {
+        let completer = UnitCompleter::new();
+        assert!(completer.candidates("10", 2).1.is_empty());
+        assert!(completer.candidates("100 m -", 7).1.is_empty());
+        assert!(completer.candidates("100 m ", 6).1.is_empty());
+        assert!(completer.candidates("1 m + 2", 7).1.is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::ui::args::Args;
+use chrono::TimeDelta;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use unit_conv::{
+    CacheConfig, ConversionCache, ExecutionContext, OpenExchangeRatesProvider, APP_ID_VAR,
+};
+
+/// Settings read from the configuration file, all of them are optional.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+pub struct Config {
+    /// API key of openexchangerates.org, the environment variable takes precedence.
+    pub api_key: Option<String>,
+    /// Unit converted from when --value is given without --from.
+    pub default_from_unit: Option<String>,
+    /// Unit converted to when --value is given without --to.
+    pub default_to_unit: Option<String>,
+    /// Decimal places used when --precision is not given.
+    pub precision: Option<usize>,
+    /// Seconds after which the cached exchange rates are requested again.
+    pub cache_ttl_seconds: Option<i64>,
+    /// SQLite database the exchange rates are cached in.
+    pub db_path: Option<PathBuf>,
+}
+
+impl Config {
+    /// Default location of the configuration file in the home directory.
+    pub fn default_path() -> PathBuf {
+        dirs::home_dir().unwrap_or_default().join(".convert.toml")
+    }
+
+    /// Read the configuration file, a missing file yields the default configuration.
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(e) => return Err(format!("Cannot read {}: {}", path.display(), e)),
+        };
+        toml::from_str(&content).map_err(|e| format!("Invalid config {}: {}", path.display(), e))
+    }
+
+    /// Fill in the flags that were not given on the command line.
+    pub fn apply(&self, args: &mut Args) {
+        args.precision = args.precision.or(self.precision);
+        // the default units only complete a conversion, they do not start one
+        if args.value.is_some() {
+            args.from = args.from.take().or_else(|| self.default_from_unit.clone());
+            args.to = args.to.take().or_else(|| self.default_to_unit.clone());
+        }
+    }
+
+    /// Context converting currencies with the configured cache and API key.
+    pub fn context(&self) -> ExecutionContext {
+        ExecutionContext::new(ConversionCache::with_config(self.cache_config()))
+    }
+
+    /// Settings of the currency cache with the configured values filled in.
+    pub fn cache_config(&self) -> CacheConfig {
+        let defaults = CacheConfig::default();
+        let provider = match &self.api_key {
+            Some(api_key) => OpenExchangeRatesProvider::with_app_id(api_key),
+            None => OpenExchangeRatesProvider::default(),
+        };
+        CacheConfig {
+            expire_after: self
+                .cache_ttl_seconds
+                .and_then(TimeDelta::try_seconds)
+                .unwrap_or(defaults.expire_after),
+            db_path: self.db_path.clone().unwrap_or(defaults.db_path),
+            provider: Arc::new(provider),
+            ..defaults
+        }
+    }
+}
+
+/// The effective configuration, the API key is never shown.
+impl Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn show<T: Display>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map_or("not set".to_string(), |value| value.to_string())
+        }
+
+        let api_key = if std::env::var(APP_ID_VAR).is_ok() {
+            format!("set (from {})", APP_ID_VAR)
+        } else if self.api_key.is_some() {
+            "set (from config file)".to_string()
+        } else {
+            "not set".to_string()
+        };
+        writeln!(f, "api_key: {}", api_key)?;
+        writeln!(f, "default_from_unit: {}", show(&self.default_from_unit))?;
+        writeln!(f, "default_to_unit: {}", show(&self.default_to_unit))?;
+        writeln!(f, "precision: {}", show(&self.precision))?;
+        writeln!(f, "cache_ttl_seconds: {}", show(&self.cache_ttl_seconds))?;
+        write!(
+            f,
+            "db_path: {}",
+            show(&self.db_path.as_ref().map(|path| path.display()))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_load_config() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(
+            file,
+            r#"
+api_key = "secret"
+default_from_unit = "m"
+default_to_unit = "ft"
+precision = 3
+cache_ttl_seconds = 3600
+db_path = "/tmp/rates.db"
+"#
+        )
+        .unwrap();
+
+        let config = Config::load(file.path()).unwrap();
+        assert_eq!(
+            config,
+            Config {
+                api_key: Some("secret".to_string()),
+                default_from_unit: Some("m".to_string()),
+                default_to_unit: Some("ft".to_string()),
+                precision: Some(3),
+                cache_ttl_seconds: Some(3600),
+                db_path: Some(PathBuf::from("/tmp/rates.db")),
+            }
+        );
+        assert!(!config.to_string().contains("secret"));
+    }
+
+    #[test]
+    fn test_missing_config() {
+        let path = std::env::temp_dir().join("unit_conv_missing_config.toml");
+        assert_eq!(Config::load(&path), Ok(Config::default()));
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "precision = \"three\"").unwrap();
+        assert!(Config::load(file.path()).is_err());
+    }
+
+    #[test]
+    fn test_apply_config() {
+        let config = Config {
+            default_from_unit: Some("m".to_string()),
+            default_to_unit: Some("ft".to_string()),
+            precision: Some(3),
+            ..Default::default()
+        };
+
+        let mut args = Args {
+            value: Some(1.0),
+            to: Some("km".to_string()),
+            ..Default::default()
+        };
+        config.apply(&mut args);
+        assert_eq!(args.from.as_deref(), Some("m"));
+        assert_eq!(args.to.as_deref(), Some("km"));
+        assert_eq!(args.precision, Some(3));
+
+        // without a value the interactive mode is started
+        let mut args = Args::default();
+        config.apply(&mut args);
+        assert_eq!(args.from, None);
+        assert_eq!(args.command(), None);
+    }
+}
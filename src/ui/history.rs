@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use unit_conv::CommandResult;
+
+/// Maximum number of entries kept in the history file, older ones are dropped.
+const MAX_ENTRIES: usize = 1000;
+
+/// A conversion entered by the user and its outcome.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Time the conversion was entered.
+    pub timestamp: DateTime<Utc>,
+    /// Expression as entered by the user.
+    pub input: String,
+    /// Output of a successful conversion.
+    pub output: Option<String>,
+    /// Error message of a failed conversion.
+    pub error: Option<String>,
+}
+
+impl HistoryEntry {
+    /// Entry for an expression entered now.
+    pub fn new(input: impl Into<String>, result: &CommandResult) -> Self {
+        let (output, error) = if result.is_error {
+            (None, Some(result.output.clone()))
+        } else {
+            (Some(result.output.clone()), None)
+        };
+        HistoryEntry {
+            timestamp: Utc::now(),
+            input: input.into(),
+            output,
+            error,
+        }
+    }
+}
+
+/// JSON file with the history entries, kept between sessions.
+/// Files of older versions with one expression per line are still read.
+pub struct HistoryStore;
+
+impl HistoryStore {
+    /// Default location of the history file in the home directory.
+    pub fn default_path() -> PathBuf {
+        dirs::home_dir()
+            .unwrap_or_default()
+            .join(".convert_history")
+    }
+
+    /// Read the entries of a history file, oldest first.
+    /// A missing or unreadable file yields an empty history.
+    pub fn load(path: &Path) -> Vec<HistoryEntry> {
+        let Ok(content) = fs::read_to_string(path) else {
+            return Vec::new();
+        };
+        if content.trim_start().starts_with('[') {
+            serde_json::from_str(&content).unwrap_or_default()
+        } else {
+            Self::migrate(&content, path)
+        }
+    }
+
+    /// Entries of a plain-text history file, dated to the last modification of the file.
+    fn migrate(content: &str, path: &Path) -> Vec<HistoryEntry> {
+        let timestamp = fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| HistoryEntry {
+                timestamp,
+                input: line.to_string(),
+                output: None,
+                error: None,
+            })
+            .collect()
+    }
+
+    /// Write the entries to a history file, keeping only the newest `MAX_ENTRIES`.
+    pub fn save(history: &[HistoryEntry], path: &Path) -> io::Result<()> {
+        let start = history.len().saturating_sub(MAX_ENTRIES);
+        let content = serde_json::to_string_pretty(&history[start..])?;
+        fs::write(path, content)
+    }
+
+    /// Delete the history file, a missing file is not an error.
+    pub fn clear(path: &Path) -> io::Result<()> {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// History file that is only used by a single test.
+    fn temp_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("unit_conv_history_{}", name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn entry(input: &str) -> HistoryEntry {
+        HistoryEntry::new(input, &CommandResult::ok("1 km"))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let path = temp_path("round_trip");
+        let history = vec![
+            entry("1000 m -> km"),
+            HistoryEntry::new("1 m -> kg", &CommandResult::error("Cannot convert")),
+        ];
+        HistoryStore::save(&history, &path).unwrap();
+
+        let loaded = HistoryStore::load(&path);
+        assert_eq!(loaded, history);
+        assert_eq!(loaded[0].timestamp, history[0].timestamp);
+        assert_eq!(loaded[1].output, None);
+        assert_eq!(loaded[1].error.as_deref(), Some("Cannot convert"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_json() {
+        let path = temp_path("json");
+        fs::write(
+            &path,
+            r#"[{"timestamp":"2024-03-01T12:30:00Z","input":"1 m -> cm","output":"100 cm","error":null}]"#,
+        )
+        .unwrap();
+
+        let loaded = HistoryStore::load(&path);
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(
+            loaded[0].timestamp,
+            DateTime::from_timestamp(1709296200, 0).unwrap()
+        );
+        assert_eq!(loaded[0].input, "1 m -> cm");
+        assert_eq!(loaded[0].output.as_deref(), Some("100 cm"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_migrate_plain_text() {
+        let path = temp_path("plain_text");
+        fs::write(&path, "1 m -> cm\n\n−10 °C -> °F\n").unwrap();
+
+        let loaded = HistoryStore::load(&path);
+        let inputs: Vec<&str> = loaded.iter().map(|entry| entry.input.as_str()).collect();
+        assert_eq!(inputs, ["1 m -> cm", "−10 °C -> °F"]);
+        assert!(loaded.iter().all(|entry| entry.output.is_none()));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let path = temp_path("missing");
+        assert!(HistoryStore::load(&path).is_empty());
+    }
+
+    #[test]
+    fn test_save_truncates_oldest() {
+        let path = temp_path("truncate");
+        let history: Vec<HistoryEntry> = (0..MAX_ENTRIES + 5)
+            .map(|i| entry(&format!("{} m -> km", i)))
+            .collect();
+        HistoryStore::save(&history, &path).unwrap();
+
+        let loaded = HistoryStore::load(&path);
+        assert_eq!(loaded.len(), MAX_ENTRIES);
+        assert_eq!(loaded.first().unwrap().input, "5 m -> km");
+        assert_eq!(loaded.last().unwrap(), &history[MAX_ENTRIES + 4]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_clear() {
+        let path = temp_path("clear");
+        HistoryStore::save(&[entry("1 m -> cm")], &path).unwrap();
+        HistoryStore::clear(&path).unwrap();
+        assert!(!path.exists());
+        assert!(HistoryStore::clear(&path).is_ok());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::ui::args::Args;
+use crate::ui::config::Config;
+use crate::ui::ui::Interface;
+use unit_conv::{ExecutionContext, Unit, Value};
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Deserialize;
+use serde_json::json;
+use std::io;
+use tokio::net::TcpListener;
+
+/// Port the server listens on when --port is not given.
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// REST interface on localhost, started with --serve.
+pub struct HttpServer {
+    port: u16,
+    /// Currency cache the conversions are executed with.
+    context: ExecutionContext,
+}
+
+/// Parameters of `GET /convert?value=100&from=m&to=km`.
+#[derive(Debug, Deserialize)]
+struct ConvertQuery {
+    value: f64,
+    from: String,
+    to: String,
+}
+
+impl HttpServer {
+    async fn serve(self) -> io::Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", self.port)).await?;
+        println!("Listening on http://{}", listener.local_addr()?);
+        axum::serve(listener, router(self.context)).await
+    }
+}
+
+impl Interface for HttpServer {
+    fn new(args: Args, config: Config) -> Self {
+        HttpServer {
+            port: args.port.unwrap_or(DEFAULT_PORT),
+            context: config.context(),
+        }
+    }
+
+    fn interact(self) {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        if let Err(e) = runtime.block_on(self.serve()) {
+            eprintln!("Server error: {}", e);
+        }
+    }
+}
+
+fn router(context: ExecutionContext) -> Router {
+    Router::new()
+        .route("/convert", get(convert))
+        .route("/units", get(units))
+        .with_state(context)
+}
+
+/// `{"result": 0.1, "unit": "km"}`, or `{"error": ...}` with status 400.
+async fn convert(
+    State(context): State<ExecutionContext>,
+    Query(query): Query<ConvertQuery>,
+) -> Response {
+    // currency rates are requested with a blocking client
+    let result = tokio::task::spawn_blocking(move || {
+        let from: Unit = query.from.parse()?;
+        let to: Unit = query.to.parse()?;
+        Value::new(query.value, from)
+            .convert_to_with(&to, &context)
+            .map_err(|e| e.to_string())
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+
+    match result {
+        Ok(v) => {
+            Json(json!({ "result": v.value(), "unit": v.unit().abbreviation() })).into_response()
+        }
+        Err(e) => (StatusCode::BAD_REQUEST, Json(json!({ "error": e }))).into_response(),
+    }
+}
+
+/// Names of all units, like the `units` command with --json.
+async fn units() -> Json<Vec<String>> {
+    Json(Unit::all_units().iter().map(Unit::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use unit_conv::{CacheConfig, ConversionCache, MockExchangeRateProvider};
+
+    /// Serve on a free port with fixed exchange rates, returning the address.
+    async fn start() -> String {
+        let context = ExecutionContext::new(ConversionCache::with_config(CacheConfig {
+            db_path: ":memory:".into(),
+            provider: Arc::new(MockExchangeRateProvider::default()),
+            ..Default::default()
+        }));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router(context)).await });
+        address
+    }
+
+    async fn get_json(url: &str) -> (reqwest::StatusCode, serde_json::Value) {
+        let client = reqwest::Client::builder().no_proxy().build().unwrap();
+        let response = client.get(url).send().await.unwrap();
+        (response.status(), response.json().await.unwrap())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_convert_route() {
+        let address = start().await;
+        let (status, json) = get_json(&format!("{}/convert?value=100&from=m&to=km", address)).await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(json, json!({ "result": 0.1, "unit": "km" }));
+
+        let (status, json) = get_json(&format!("{}/convert?value=1&from=m&to=kg", address)).await;
+        assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+        assert!(json["error"].is_string());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_units_route() {
+        let address = start().await;
+        let (status, json) = get_json(&format!("{}/units", address)).await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        let units = json.as_array().unwrap();
+        assert_eq!(units.len(), Unit::all_units().len());
+        assert!(units.contains(&json!("kilometer (km)")));
+    }
+}
@@ -1,2 +1,12 @@
+pub mod args;
 pub mod cli;
+pub mod completer;
+pub mod config;
+pub mod history;
+#[cfg(feature = "http-server")]
+pub mod http;
+pub mod output;
+pub mod style;
+pub mod tui;
+#[allow(clippy::module_inception)]
 pub mod ui;
@@ -0,0 +1,96 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Destination of conversion results, selected through --output-file and --quiet.
+pub enum OutputWriter {
+    /// Print results only.
+    Stdout,
+    /// Append results to a file only.
+    File(File),
+    /// Print results and append them to a file.
+    Both(File),
+}
+
+impl OutputWriter {
+    /// Open the output file for appending, if one is given.
+    pub fn open(path: Option<&Path>, quiet: bool) -> io::Result<Self> {
+        let Some(path) = path else {
+            return Ok(OutputWriter::Stdout);
+        };
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(if quiet {
+            OutputWriter::File(file)
+        } else {
+            OutputWriter::Both(file)
+        })
+    }
+
+    /// Whether results should be printed.
+    pub fn to_stdout(&self) -> bool {
+        !matches!(self, OutputWriter::File(_))
+    }
+
+    /// Append a result to the output file, if there is one.
+    pub fn write_file(&mut self, output: &str) -> io::Result<()> {
+        match self {
+            OutputWriter::Stdout => Ok(()),
+            OutputWriter::File(file) | OutputWriter::Both(file) => {
+                writeln!(file, "{}", output)?;
+                file.flush()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_stdout() {
+        let mut writer = OutputWriter::open(None, false).unwrap();
+        assert!(matches!(writer, OutputWriter::Stdout));
+        assert!(writer.to_stdout());
+        assert!(writer.write_file("100 cm").is_ok());
+    }
+
+    #[test]
+    fn test_write_file() {
+        let file = NamedTempFile::new().unwrap();
+        let mut writer = OutputWriter::open(Some(file.path()), false).unwrap();
+        assert!(matches!(writer, OutputWriter::Both(_)));
+        assert!(writer.to_stdout());
+
+        writer.write_file("100 cm").unwrap();
+        writer.write_file("2 km").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "100 cm\n2 km\n"
+        );
+    }
+
+    #[test]
+    fn test_quiet_appends() {
+        let file = NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "1 m\n").unwrap();
+
+        let mut writer = OutputWriter::open(Some(file.path()), true).unwrap();
+        assert!(matches!(writer, OutputWriter::File(_)));
+        assert!(!writer.to_stdout());
+
+        writer.write_file(r#"{"output":"100 cm"}"#).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(file.path()).unwrap(),
+            "1 m\n{\"output\":\"100 cm\"}\n"
+        );
+    }
+
+    #[test]
+    fn test_open_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing").join("results.txt");
+        assert!(OutputWriter::open(Some(&path), false).is_err());
+    }
+}
@@ -0,0 +1,131 @@
+use console::{style, Color};
+use unit_conv::CommandResult;
+
+/// Whether the output is colored, selected through --color and --no-color.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Color the output if it is printed to a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Mode requested through the environment, see https://no-color.org.
+    /// A non-empty `NO_COLOR` or a dumb terminal disable the colors.
+    pub fn from_env() -> ColorMode {
+        ColorMode::from_vars(
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_vars(no_color: Option<&str>, term: Option<&str>) -> ColorMode {
+        if no_color.is_some_and(|value| !value.is_empty()) || term == Some("dumb") {
+            ColorMode::Never
+        } else {
+            ColorMode::Auto
+        }
+    }
+}
+
+/// Colors of the printed output: results in green, units in yellow, errors in red.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OutputStyle {
+    pub color: ColorMode,
+}
+
+impl OutputStyle {
+    /// Color the text, unless the colors are disabled.
+    pub fn apply_color(&self, text: &str, color: Color) -> String {
+        let styled = style(text).fg(color);
+        match self.color {
+            ColorMode::Auto => styled.to_string(),
+            ColorMode::Always => styled.force_styling(true).to_string(),
+            ColorMode::Never => text.to_string(),
+        }
+    }
+
+    /// Color the output of a command, the number and unit of a conversion separately.
+    pub fn result(&self, result: &CommandResult) -> String {
+        if result.is_error {
+            return self.apply_color(&result.output, Color::Red);
+        }
+        let converted = result
+            .conversion
+            .and_then(|conversion| conversion.output)
+            .is_some();
+        match result.output.split_once(' ') {
+            Some((value, unit)) if converted && !result.output.contains('\n') => format!(
+                "{} {}",
+                self.apply_color(value, Color::Green),
+                self.apply_color(unit, Color::Yellow)
+            ),
+            _ => result.output.clone(),
+        }
+    }
+
+    /// The arrow separating a value from the unit it is converted to.
+    pub fn arrow(&self) -> String {
+        self.apply_color("->", Color::Cyan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use unit_conv::{Command, ExecutionContext};
+
+    #[test]
+    fn test_apply_color() {
+        let never = OutputStyle {
+            color: ColorMode::Never,
+        };
+        assert_eq!(never.apply_color("foo", Color::Green), "foo");
+
+        let always = OutputStyle {
+            color: ColorMode::Always,
+        };
+        let colored = always.apply_color("foo", Color::Green);
+        assert!(colored.starts_with("\u{1b}["));
+        assert!(colored.contains("foo"));
+        assert_eq!(console::strip_ansi_codes(&colored), "foo");
+    }
+
+    #[test]
+    fn test_color_mode_from_env() {
+        assert_eq!(ColorMode::from_vars(None, None), ColorMode::Auto);
+        assert_eq!(
+            ColorMode::from_vars(None, Some("xterm-256color")),
+            ColorMode::Auto
+        );
+        assert_eq!(ColorMode::from_vars(Some("1"), None), ColorMode::Never);
+        assert_eq!(ColorMode::from_vars(Some(""), None), ColorMode::Auto);
+        assert_eq!(ColorMode::from_vars(None, Some("dumb")), ColorMode::Never);
+    }
+
+    #[test]
+    fn test_result_colors() {
+        let style = OutputStyle {
+            color: ColorMode::Always,
+        };
+        let result = "1 km -> m"
+            .parse::<Command>()
+            .unwrap()
+            .execute(ExecutionContext::global());
+        assert_eq!(
+            style.result(&result),
+            format!(
+                "{} {}",
+                style.apply_color("1000", Color::Green),
+                style.apply_color("m", Color::Yellow)
+            )
+        );
+        assert_eq!(
+            style.result(&CommandResult::error("oops")),
+            style.apply_color("oops", Color::Red)
+        );
+        assert_eq!(style.result(&CommandResult::ok("plain")), "plain");
+    }
+}
@@ -0,0 +1,203 @@
+use crate::ui::args::Args;
+use crate::ui::cli::DEFAULT_PRECISION;
+use crate::ui::config::Config;
+use crate::ui::ui::Interface;
+use unit_conv::{Command, CommandResult, ExecutionContext, FormatOptions};
+
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::execute;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Paragraph};
+use ratatui::{Frame, Terminal};
+use std::io::{self, stdout};
+
+/// Full screen interface with the conversions on top and the input field at the bottom.
+pub struct Tui {
+    options: FormatOptions,
+    /// Settings from the configuration file.
+    config: Config,
+    /// Currency cache the commands are executed with.
+    context: ExecutionContext,
+    /// Text typed into the input field.
+    input: String,
+    /// Submitted expressions with their results, oldest first.
+    history: Vec<(String, CommandResult)>,
+    /// Number of lines the history is scrolled up from its end.
+    scroll: u16,
+}
+
+impl Tui {
+    /// Execute the current input and add it to the history.
+    /// Returns `true` when the program should exit.
+    fn submit(&mut self) -> bool {
+        let input = std::mem::take(&mut self.input);
+        let input = input.trim();
+        if input.is_empty() {
+            return false;
+        }
+
+        let result = match input.parse::<Command>() {
+            Ok(Command::Config) => CommandResult::ok(self.config.to_string()),
+            Ok(command) => command.execute_with(&self.context, &self.options),
+            Err(e) => CommandResult::error(e),
+        };
+        if result.exit {
+            return true;
+        }
+        self.history.push((input.to_string(), result));
+        self.scroll = 0;
+        false
+    }
+
+    /// Handle a key press. Returns `true` when the program should exit.
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Esc => return true,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return true,
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Char(c) => self.input.push(c),
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Up => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Down => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        false
+    }
+
+    fn history_lines(&self) -> Vec<Line<'_>> {
+        let mut lines = Vec::new();
+        for (input, result) in &self.history {
+            lines.push(Line::styled(
+                format!("> {}", input),
+                Style::default().fg(Color::Cyan),
+            ));
+            let style = if result.is_error {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default()
+            };
+            for line in result.output.lines() {
+                lines.push(Line::styled(line.to_string(), style));
+            }
+        }
+        lines
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [history_area, input_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(3)]).areas(frame.size());
+
+        // show the end of the history unless the user scrolled up
+        let lines = self.history_lines();
+        let visible = history_area.height.saturating_sub(2);
+        let max_offset = (lines.len() as u16).saturating_sub(visible);
+        let offset = max_offset.saturating_sub(self.scroll);
+        let history = Paragraph::new(lines)
+            .block(Block::default().borders(Borders::ALL).title("Conversions"))
+            .scroll((offset, 0));
+        frame.render_widget(history, history_area);
+
+        let input = Paragraph::new(self.input.as_str()).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Input (Enter to convert, Esc to exit)"),
+        );
+        frame.render_widget(input, input_area);
+        frame.set_cursor(
+            input_area.x + 1 + self.input.chars().count() as u16,
+            input_area.y + 1,
+        );
+    }
+
+    fn run(&mut self) -> io::Result<()> {
+        enable_raw_mode()?;
+        execute!(stdout(), EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
+
+        let result = loop {
+            if let Err(e) = terminal.draw(|frame| self.draw(frame)) {
+                break Err(e);
+            }
+            match event::read() {
+                Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                    if self.handle_key(key.code, key.modifiers) {
+                        break Ok(());
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => break Err(e),
+            }
+        };
+
+        disable_raw_mode()?;
+        execute!(stdout(), LeaveAlternateScreen)?;
+        result
+    }
+}
+
+impl Interface for Tui {
+    fn new(args: Args, config: Config) -> Self {
+        Tui {
+            options: FormatOptions {
+                verbose: args.verbose,
+                precision: Some(args.precision.unwrap_or(DEFAULT_PRECISION)),
+                base: args.numeral_system(),
+            },
+            context: config.context(),
+            config,
+            input: String::new(),
+            history: Vec::new(),
+            scroll: 0,
+        }
+    }
+
+    fn interact(mut self) {
+        if let Err(e) = self.run() {
+            eprintln!("Terminal error: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn type_text(tui: &mut Tui, text: &str) {
+        for c in text.chars() {
+            tui.handle_key(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+    }
+
+    #[test]
+    fn test_submit() {
+        let mut tui = Tui::new(Args::default(), Config::default());
+        type_text(&mut tui, "1 km -> m");
+        assert!(!tui.handle_key(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(tui.input.is_empty());
+        assert_eq!(tui.history.len(), 1);
+        assert_eq!(tui.history[0].1.output, "1000.000000 m");
+
+        type_text(&mut tui, "1 km -> kg");
+        tui.handle_key(KeyCode::Enter, KeyModifiers::NONE);
+        assert!(tui.history[1].1.is_error);
+
+        type_text(&mut tui, "exit");
+        assert!(tui.handle_key(KeyCode::Enter, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_exit_keys() {
+        let mut tui = Tui::new(Args::default(), Config::default());
+        assert!(tui.handle_key(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(tui.handle_key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(!tui.handle_key(KeyCode::Char('c'), KeyModifiers::NONE));
+    }
+}
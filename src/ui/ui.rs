@@ -1,4 +1,7 @@
+use crate::ui::args::Args;
+use crate::ui::config::Config;
+
 pub trait Interface {
-    fn new() -> Self;
+    fn new(args: Args, config: Config) -> Self;
     fn interact(self);
 }
@@ -0,0 +1,53 @@
+#![cfg(feature = "async")]
+
+use std::sync::Arc;
+use unit_conv::{
+    CacheConfig, ConversionCache, CurrencyUnit, ExecutionContext, LengthUnit,
+    MockExchangeRateProvider, Unit, Value,
+};
+
+/// Context with fixed exchange rates, 1 USD is 0.9 EUR.
+fn mock_context() -> ExecutionContext {
+    ExecutionContext::new(ConversionCache::with_config(CacheConfig {
+        db_path: ":memory:".into(),
+        provider: Arc::new(MockExchangeRateProvider::default()),
+        ..Default::default()
+    }))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sync_conversion_inside_runtime() {
+    let value = Value::new(1.0, Unit::Length(LengthUnit::Meter));
+    let km = value.convert_to(&Unit::Length(LengthUnit::Kilometer));
+    assert_eq!(km.unwrap().to_string(), "0.001 km");
+
+    // the blocking currency lookup must not panic inside the runtime
+    let ctx = mock_context();
+    let value = Value::new(10.0, Unit::Currency(CurrencyUnit::USD));
+    let eur = value.convert_to_with(&Unit::Currency(CurrencyUnit::EUR), &ctx);
+    assert!((eur.unwrap().value().unwrap() - 9.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_convert_async() {
+    let ctx = mock_context();
+    let result = CurrencyUnit::convert_async(10.0, &CurrencyUnit::USD, &CurrencyUnit::EUR, &ctx);
+    assert!((result.await.unwrap() - 9.0).abs() < 1e-9);
+    let result = CurrencyUnit::convert_async(1.0, &CurrencyUnit::USD, &CurrencyUnit::USD, &ctx);
+    assert_eq!(result.await.unwrap(), 1.0);
+}
+
+#[tokio::test]
+async fn test_to_base_unit_async() {
+    let ctx = mock_context();
+    let usd = CurrencyUnit::GBP.to_base_unit_async(8.0, &ctx).await;
+    assert!((usd.unwrap() - 10.0).abs() < 1e-9);
+}
+
+#[tokio::test(flavor = "current_thread")]
+async fn test_sync_conversion_inside_current_thread_runtime() {
+    let ctx = mock_context();
+    let value = Value::new(10.0, Unit::Currency(CurrencyUnit::USD));
+    let eur = value.convert_to_with(&Unit::Currency(CurrencyUnit::EUR), &ctx);
+    assert!((eur.unwrap().value().unwrap() - 9.0).abs() < 1e-9);
+}
@@ -0,0 +1,33 @@
+use std::io::Cursor;
+
+use unit_conv::{process_batch, ExecutionContext};
+
+#[test]
+fn test_process_batch() {
+    let input = "\
+# lengths
+1 m -> cm
+
+2 kg -> g
+1 m -> kg
+not a command
+";
+    let results = process_batch(Cursor::new(input), ExecutionContext::global());
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0], Ok("100 cm".to_string()));
+    assert_eq!(results[1], Ok("2000 g".to_string()));
+    assert!(results[2]
+        .as_ref()
+        .unwrap_err()
+        .starts_with("Conversion error"));
+    assert!(results[3].is_err());
+}
+
+#[test]
+fn test_process_batch_stops_at_exit() {
+    let results = process_batch(
+        Cursor::new("1 m -> cm\nexit\n1 m -> km\n"),
+        ExecutionContext::global(),
+    );
+    assert_eq!(results.len(), 1);
+}
@@ -0,0 +1,43 @@
+use std::io::Cursor;
+
+use unit_conv::{execute_batch, CsvFormatter, ExecutionContext};
+
+#[test]
+fn test_csv_formatter() {
+    let input = "1 m -> cm\n2 kg -> g\n0 C -> K\n1 m -> kg\n";
+    let mut formatter = CsvFormatter::new(Vec::new());
+    for result in execute_batch(Cursor::new(input), ExecutionContext::global()) {
+        formatter.write(&result).unwrap();
+    }
+    let output = formatter.into_inner();
+
+    let mut reader = csv::Reader::from_reader(output.as_slice());
+    let headers = reader.headers().unwrap().clone();
+    assert_eq!(
+        headers.iter().collect::<Vec<_>>(),
+        [
+            "input_value",
+            "input_unit",
+            "output_value",
+            "output_unit",
+            "error"
+        ]
+    );
+
+    let records: Vec<csv::StringRecord> = reader.records().map(Result::unwrap).collect();
+    assert_eq!(records.len(), 4);
+    assert!(records.iter().all(|record| record.len() == 5));
+    assert_eq!(
+        records[0].iter().collect::<Vec<_>>(),
+        ["1", "m", "100", "cm", ""]
+    );
+    assert_eq!(&records[2][2], "273.15");
+    for record in &records[..3] {
+        assert!(record[4].is_empty());
+    }
+
+    // the failed conversion keeps its input
+    assert_eq!(&records[3][0], "1");
+    assert!(records[3][2].is_empty());
+    assert!(records[3][4].starts_with("Conversion error"));
+}
@@ -0,0 +1,72 @@
+use unit_conv::{ConversionErrorKind, LengthUnit, MassUnit, TemperatureUnit, Unit, Value};
+
+#[test]
+fn test_meter_to_kilometer() {
+    let value = Value::new(1500.0, Unit::Length(LengthUnit::Meter));
+    let result = value.convert_to(&Unit::Length(LengthUnit::Kilometer));
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap().to_string(), "1.5 km");
+}
+
+#[test]
+fn test_parse_unit() {
+    let unit = "km".parse::<Unit>();
+    assert_eq!(unit, Ok(Unit::Length(LengthUnit::Kilometer)));
+}
+
+#[test]
+fn test_convert() {
+    let result = unit_conv::convert(
+        100.0,
+        Unit::Length(LengthUnit::Meter),
+        Unit::Length(LengthUnit::Kilometer),
+    );
+    assert_eq!(result, Ok(0.1));
+}
+
+#[test]
+fn test_convert_below_absolute_zero() {
+    let kelvin = Unit::Temperature(TemperatureUnit::Kelvin);
+    let rankine = Unit::Temperature(TemperatureUnit::Rankine);
+    let result = unit_conv::convert(-1.0, kelvin, rankine);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        ConversionErrorKind::NegativeTemperature
+    );
+
+    let result = Value::new(-1.0, kelvin).convert_to(&rankine);
+    assert_eq!(
+        result.unwrap_err().kind(),
+        ConversionErrorKind::NegativeTemperature
+    );
+}
+
+#[test]
+fn test_convert_value() {
+    let result = unit_conv::convert_value(
+        100.0,
+        Unit::Length(LengthUnit::Meter),
+        Unit::Length(LengthUnit::Kilometer),
+    );
+    assert_eq!(result.unwrap().to_string(), "0.1 km");
+}
+
+#[test]
+fn test_convert_cross_category() {
+    let result = unit_conv::convert(
+        1.0,
+        Unit::Length(LengthUnit::Meter),
+        Unit::Mass(MassUnit::Kilogram),
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "Conversion error: Cannot convert from meter (m) to kilogram (kg)"
+    );
+
+    let result = unit_conv::convert_value(
+        1.0,
+        Unit::Length(LengthUnit::Meter),
+        Unit::Mass(MassUnit::Kilogram),
+    );
+    assert!(result.is_err());
+}
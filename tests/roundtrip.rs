@@ -0,0 +1,57 @@
+use proptest::prelude::*;
+use strum::IntoEnumIterator;
+use unit_conv::{Convertable, LengthUnit, MassUnit, TemperatureUnit};
+
+fn length_unit() -> impl Strategy<Value = LengthUnit> {
+    prop::sample::select(LengthUnit::iter().collect::<Vec<_>>())
+}
+
+fn mass_unit() -> impl Strategy<Value = MassUnit> {
+    prop::sample::select(MassUnit::iter().collect::<Vec<_>>())
+}
+
+fn temperature_unit() -> impl Strategy<Value = TemperatureUnit> {
+    prop::sample::select(TemperatureUnit::iter().collect::<Vec<_>>())
+}
+
+/// Finite positive values, small and large enough to not over- or underflow with any factor.
+fn value() -> impl Strategy<Value = f64> {
+    1e-6..1e6
+}
+
+/// Largest deviation of a roundtrip of `value`. Linear units only scale the value, which is
+/// rounded at most four times, each by half an epsilon relative to the value. Non-linear ones
+/// add and remove an offset, so small values lose precision relative to the offset instead.
+fn tolerance<U: Convertable>(value: f64, from: &U, to: &U) -> f64 {
+    if from.is_linear() && to.is_linear() {
+        f64::EPSILON * value * 2.0
+    } else {
+        f64::EPSILON * value.max(500.0) * 10.0
+    }
+}
+
+/// Convert `value` from `from` to `to` and back again.
+fn roundtrip<U: Convertable>(value: f64, from: &U, to: &U) -> f64 {
+    let converted = U::convert(value, from, to).unwrap();
+    U::convert(converted, to, from).unwrap()
+}
+
+proptest! {
+    #[test]
+    fn test_length_roundtrip(v in value(), from in length_unit(), to in length_unit()) {
+        let result = roundtrip(v, &from, &to);
+        prop_assert!((result - v).abs() <= tolerance(v, &from, &to), "{} became {}", v, result);
+    }
+
+    #[test]
+    fn test_mass_roundtrip(v in value(), from in mass_unit(), to in mass_unit()) {
+        let result = roundtrip(v, &from, &to);
+        prop_assert!((result - v).abs() <= tolerance(v, &from, &to), "{} became {}", v, result);
+    }
+
+    #[test]
+    fn test_temperature_roundtrip(v in value(), from in temperature_unit(), to in temperature_unit()) {
+        let result = roundtrip(v, &from, &to);
+        prop_assert!((result - v).abs() <= tolerance(v, &from, &to), "{} became {}", v, result);
+    }
+}